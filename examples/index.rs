@@ -51,7 +51,7 @@ async fn main() -> Result<()> {
         .auto_build_policy(
             api::AutoBuildPolicyBuilder::default()
                 .policy_type(AutoBuildPolicyType::PERIODICAL)
-                .period_in_second(5000 as u64)
+                .period_in_second(5000_u64)
                 .build()?,
         )
         .build()?;
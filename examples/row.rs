@@ -29,8 +29,8 @@ async fn main() -> Result<()> {
 
     // insert row with a defined struct
     let insert_args1 = api::InsertRowArgsBuilder::default()
-        .database(&TESTDATABSE.to_string())
-        .table(&TESTTABLE.to_string())
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
         .rows(vec![MyRecord {
             id: "0001".to_string(),
             book_name: "西游记".to_string(),
@@ -44,8 +44,8 @@ async fn main() -> Result<()> {
 
     // insert row with a json object
     let insert_args2 = api::InsertRowArgsBuilder::default()
-        .database(&TESTDATABSE.to_string())
-        .table(&TESTTABLE.to_string())
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
         .rows(vec![serde_json::json!({
             "id": "0002",
             "bookName": "西游记",
@@ -59,8 +59,8 @@ async fn main() -> Result<()> {
 
     // query the data you insert
     let query_args = api::QueryRowArgsBuilder::default()
-        .database(&TESTDATABSE.to_string())
-        .table(&TESTTABLE.to_string())
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
         .primary_key(serde_json::json!({
             "id": "0001",
         }))
@@ -78,8 +78,8 @@ async fn main() -> Result<()> {
 
     // update row
     let update_args = api::UpdateRowArgsBuilder::default()
-        .database(&TESTDATABSE.to_string())
-        .table(&TESTTABLE.to_string())
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
         .primary_key(serde_json::json!({
             "id": "0001",
         }))
@@ -95,8 +95,8 @@ async fn main() -> Result<()> {
     // delete data
     // you can delete data by primary key or filter
     let delete_args = api::DeleteRowArgsBuilder::default()
-        .database(&TESTDATABSE.to_string())
-        .table(&TESTTABLE.to_string())
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
         // .primary_key(serde_json::json!({
         //     "id": "0001",
         // }))
@@ -107,8 +107,8 @@ async fn main() -> Result<()> {
 
     // upsert data
     let upsert_args = api::UpsertRowArgsBuilder::default()
-        .database(&TESTDATABSE.to_string())
-        .table(&TESTTABLE.to_string())
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
         .rows(vec![
             serde_json::json!({
                 "id":       "0001",
@@ -145,15 +145,15 @@ async fn main() -> Result<()> {
 
     // select data
     let mut select_args = api::SelectRowsArgsBuilder::default()
-        .database(&TESTDATABSE.to_string())
-        .table(&TESTTABLE.to_string())
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
         .projections(vec![
             "id".to_string(),
             "bookName".to_string(),
             "page".to_string(),
         ])
         .filter("page > 21")
-        .limit(1 as u32)
+        .limit(1_u32)
         .build()?;
     loop {
         let ret = client
@@ -169,8 +169,8 @@ async fn main() -> Result<()> {
 
     // search data
     let search_args = api::SearchRowsArgsBuilder::default()
-        .database(&TESTDATABSE.to_string())
-        .table(&TESTTABLE.to_string())
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
         .anns(
             api::AnnsSearchParamsBuilder::default()
                 .vector_field("vector")
@@ -206,8 +206,8 @@ async fn main() -> Result<()> {
         .filter("bookName = '三国演义'")
         .build()?;
     let batch_search_args = api::BatchSearchRowsArgsBuilder::default()
-        .database(&TESTDATABSE.to_string())
-        .table(&TESTTABLE.to_string())
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
         .anns(batch_ann_params)
         .retrieve_vector(true)
         .build()?;
@@ -69,15 +69,12 @@ async fn main() -> Result<()> {
         .database("book2")
         .table("book_segments")
         .description("basic test")
-        .replication(3 as u32)
+        .replication(3_u32)
         .partition(api::Partition {
             partition_type: PartitionType::HASH,
             partition_num: 3,
         })
-        .schema(api::TableSchema {
-            fields: fields,
-            indexes: indexes,
-        })
+        .schema(api::TableSchema { fields, indexes })
         .build()?;
     let create_table_resp = client.create_table(&create_tabke_args).await?;
     println!("create table resp: {:?}", create_table_resp);
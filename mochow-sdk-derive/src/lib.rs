@@ -0,0 +1,559 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+//! derive macros of mochow-sdk-rust, use them by the re-exports of `mochow_sdk_rust::mochow::api`
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    meta::ParseNestedMeta, parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput,
+    Fields, GenericArgument, LitBool, LitFloat, LitInt, LitStr, PathArguments, Type,
+};
+
+/// derive `mochow_sdk_rust::mochow::api::MochowTable` for a struct of named fields,
+/// the table schema is generated from the fields:
+/// field names honor `#[serde(rename)]` and `#[serde(rename_all)]`,
+/// fields with `#[serde(skip)]`, `#[serde(skip_serializing)]` or `#[mochow(skip)]` are ignored,
+/// the field type is inferred from the rust type, `Option<T>` is the same as `T`.
+///
+/// field attributes of `#[mochow(..)]`:
+/// - `primary_key`, `partition_key`, `auto_increment`, `not_null`
+/// - `dimension = 3`, required by vector fields
+/// - `field_type = "TEXT"`, overrides the inferred field type
+/// - `index(name = "vector_idx", index_type = "HNSW", metric_type = "L2", m = 16, ef_construction = 200)`,
+///   the name defaults to "{field}_idx" and the index type defaults to SECONDARY for scalar fields,
+///   other params are `nsq`, `sample_rate`, `coarse_cluster_count`, `fine_cluster_count` and `auto_build`
+#[proc_macro_derive(MochowTable, attributes(mochow))]
+pub fn derive_mochow_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct IndexAttr {
+    name: Option<String>,
+    index_type: Option<String>,
+    metric_type: Option<String>,
+    m: Option<u32>,
+    ef_construction: Option<u32>,
+    nsq: Option<u32>,
+    sample_rate: Option<f64>,
+    coarse_cluster_count: Option<u32>,
+    fine_cluster_count: Option<u32>,
+    auto_build: bool,
+}
+
+#[derive(Default)]
+struct FieldAttr {
+    skip: bool,
+    rename: Option<String>,
+    primary_key: bool,
+    partition_key: bool,
+    auto_increment: bool,
+    not_null: bool,
+    dimension: Option<u32>,
+    field_type: Option<(String, Span)>,
+    indexes: Vec<(IndexAttr, Span)>,
+}
+
+const FIELD_TYPES: &[(&str, &str)] = &[
+    ("BOOL", "BOOL"),
+    ("INT8", "INT8"),
+    ("UINT8", "UINT8"),
+    ("INT16", "Int16"),
+    ("UINT16", "Uint16"),
+    ("INT32", "INT32"),
+    ("UINT32", "UINT32"),
+    ("INT64", "INT64"),
+    ("UINT64", "UINT64"),
+    ("FLOAT", "FLOAT"),
+    ("DOUBLE", "DOUBLE"),
+    ("DATE", "DATE"),
+    ("DATETIME", "DATETIME"),
+    ("TIMESTAMP", "TIMESTAMP"),
+    ("STRING", "STRING"),
+    ("BINARY", "BINARY"),
+    ("UUID", "UUID"),
+    ("TEXT", "TEXT"),
+    ("TEXT_GBK", "TEXT_GBK"),
+    ("TEXT_GB18030", "TEXT_GB18030"),
+    ("FLOAT_VECTOR", "FLOAT_VECTOR"),
+];
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "MochowTable only supports structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "MochowTable only supports structs",
+            ))
+        }
+    };
+    let rename_all = container_rename_all(&input.attrs)?;
+
+    let api = quote!(::mochow_sdk_rust::mochow::api);
+    let mut field_schemas = vec![];
+    let mut index_schemas = vec![];
+    let mut projections = vec![];
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let attr = field_attr(&field.attrs)?;
+        if attr.skip {
+            continue;
+        }
+        let rust_name = ident.to_string();
+        let rust_name = rust_name.trim_start_matches("r#");
+        let name = match (&attr.rename, &rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some((rule, span))) => apply_rename_rule(rust_name, rule, *span)?,
+            (None, None) => rust_name.to_string(),
+        };
+        let field_type = match &attr.field_type {
+            Some((field_type, span)) => FIELD_TYPES
+                .iter()
+                .find(|(name, _)| name == field_type)
+                .map(|(_, variant)| variant.to_string())
+                .ok_or_else(|| {
+                    syn::Error::new(*span, format!("unknown field_type {}", field_type))
+                })?,
+            None => infer_field_type(&field.ty).ok_or_else(|| {
+                syn::Error::new(
+                    field.ty.span(),
+                    "can not infer the mochow field type, specify it by #[mochow(field_type = \"..\")]",
+                )
+            })?,
+        };
+        let is_vector = field_type == "FLOAT_VECTOR";
+        let dimension = match (is_vector, attr.dimension) {
+            (true, Some(dimension)) => quote!(.dimension(#dimension)),
+            (true, None) => {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "vector field requires #[mochow(dimension = ..)]",
+                ))
+            }
+            (false, Some(_)) => {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "dimension is only applicable to vector fields",
+                ))
+            }
+            (false, None) => quote!(),
+        };
+        let variant = format_ident!("{}", field_type);
+        let (primary_key, partition_key, auto_increment, not_null) = (
+            attr.primary_key,
+            attr.partition_key,
+            attr.auto_increment,
+            attr.not_null,
+        );
+        field_schemas.push(quote! {
+            #api::FieldSchemaBuilder::default()
+                .field_name(#name)
+                .field_type(#api::FieldType::#variant)
+                .primary_key(#primary_key)
+                .partition_key(#partition_key)
+                .auto_increment(#auto_increment)
+                .not_null(#not_null)
+                #dimension
+                .build()
+                .expect("valid field schema")
+        });
+        for (index, span) in &attr.indexes {
+            index_schemas.push(index_schema(&api, &name, is_vector, index, *span)?);
+        }
+        projections.push(name);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #api::MochowTable for #ident #ty_generics #where_clause {
+            fn table_schema() -> #api::TableSchema {
+                #api::TableSchema {
+                    fields: vec![#(#field_schemas),*],
+                    indexes: vec![#(#index_schemas),*],
+                }
+            }
+
+            fn projections() -> Vec<String> {
+                vec![#(#projections.to_string()),*]
+            }
+        }
+    })
+}
+
+fn index_schema(
+    api: &TokenStream2,
+    field: &str,
+    is_vector: bool,
+    index: &IndexAttr,
+    span: Span,
+) -> syn::Result<TokenStream2> {
+    let name = index
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("{}_idx", field));
+    let index_type = match (&index.index_type, is_vector) {
+        (Some(index_type), _) => index_type.clone(),
+        (None, false) => "SECONDARY".to_string(),
+        (None, true) => {
+            return Err(syn::Error::new(
+                span,
+                "vector index requires index_type, one of FLAT, HNSW, HNSWPQ and PUCK",
+            ))
+        }
+    };
+    let missing = |param: &str| {
+        syn::Error::new(
+            span,
+            format!("{} index requires parameter {}", index_type, param),
+        )
+    };
+    let (variant, params) = match index_type.as_str() {
+        "SECONDARY" => (quote!(SECONDARY_INDEX), quote!()),
+        "FLAT" => (quote!(FLAT), quote!()),
+        "HNSW" => {
+            let m = index.m.ok_or_else(|| missing("m"))?;
+            let ef_construction = index
+                .ef_construction
+                .ok_or_else(|| missing("ef_construction"))?;
+            (
+                quote!(HNSW),
+                quote!(.params(#api::VectorIndexParams::HNSW(#api::HNSWIndexParam {
+                    m: #m,
+                    ef_construction: #ef_construction,
+                }))),
+            )
+        }
+        "HNSWPQ" => {
+            let m = index.m.ok_or_else(|| missing("m"))?;
+            let ef_construction = index
+                .ef_construction
+                .ok_or_else(|| missing("ef_construction"))?;
+            let nsq = index.nsq.ok_or_else(|| missing("nsq"))?;
+            let sample_rate = index.sample_rate.ok_or_else(|| missing("sample_rate"))?;
+            (
+                quote!(HNSWPQ),
+                quote!(.params(#api::VectorIndexParams::HNSWPQ(#api::HNSWPQIndexParam {
+                    m: #m,
+                    ef_construction: #ef_construction,
+                    nsq: #nsq,
+                    sample_rate: #sample_rate,
+                }))),
+            )
+        }
+        "PUCK" => {
+            let coarse = index
+                .coarse_cluster_count
+                .ok_or_else(|| missing("coarse_cluster_count"))?;
+            let fine = index
+                .fine_cluster_count
+                .ok_or_else(|| missing("fine_cluster_count"))?;
+            (
+                quote!(PUCK),
+                quote!(.params(#api::VectorIndexParams::PUCK(#api::PUCKIndexParam {
+                    coarse_cluster_count: #coarse,
+                    fine_cluster_count: #fine,
+                }))),
+            )
+        }
+        other => {
+            return Err(syn::Error::new(
+                span,
+                format!("unknown index_type {}", other),
+            ))
+        }
+    };
+    let metric_type = match (&index.metric_type, is_vector) {
+        (Some(metric), true) => {
+            let metric = match metric.as_str() {
+                "L2" | "IP" | "COSINE" => format_ident!("{}", metric),
+                other => {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("unknown metric_type {}", other),
+                    ))
+                }
+            };
+            quote!(.metric_type(#api::MetricType::#metric))
+        }
+        (None, true) => {
+            return Err(syn::Error::new(
+                span,
+                "vector index requires metric_type, one of L2, IP and COSINE",
+            ))
+        }
+        (Some(_), false) => {
+            return Err(syn::Error::new(
+                span,
+                "metric_type is only applicable to vector index",
+            ))
+        }
+        (None, false) => quote!(),
+    };
+    let auto_build = index.auto_build;
+    Ok(quote! {
+        #api::IndexSchemaBuilder::default()
+            .index_name(#name)
+            .field(#field)
+            .index_type(#api::IndexType::#variant)
+            .auto_build(#auto_build)
+            #metric_type
+            #params
+            .build()
+            .expect("valid index schema")
+    })
+}
+
+// consume the value of an unrelated attribute item
+fn skip_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        content.parse::<TokenStream2>()?;
+    }
+    Ok(())
+}
+
+// the serialized name of `rename = ".."` or `rename(serialize = "..")`
+fn serialize_name(meta: &ParseNestedMeta) -> syn::Result<Option<String>> {
+    if meta.input.peek(syn::Token![=]) {
+        return Ok(Some(meta.value()?.parse::<LitStr>()?.value()));
+    }
+    let mut name = None;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("serialize") {
+            name = Some(inner.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else {
+            skip_meta(&inner)
+        }
+    })?;
+    Ok(name)
+}
+
+fn container_rename_all(attrs: &[Attribute]) -> syn::Result<Option<(String, Span)>> {
+    let mut rename_all = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let span = meta.path.span();
+                if let Some(rule) = serialize_name(&meta)? {
+                    rename_all = Some((rule, span));
+                }
+                Ok(())
+            } else {
+                skip_meta(&meta)
+            }
+        })?;
+    }
+    Ok(rename_all)
+}
+
+fn field_attr(attrs: &[Attribute]) -> syn::Result<FieldAttr> {
+    let mut ret = FieldAttr::default();
+    for attr in attrs {
+        if attr.path().is_ident("serde") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if let Some(name) = serialize_name(&meta)? {
+                        ret.rename = Some(name);
+                    }
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                    ret.skip = true;
+                } else if meta.path.is_ident("flatten") {
+                    return Err(meta.error("MochowTable does not support flattened fields"));
+                } else {
+                    skip_meta(&meta)?;
+                }
+                Ok(())
+            })?;
+        } else if attr.path().is_ident("mochow") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    ret.skip = true;
+                } else if meta.path.is_ident("primary_key") {
+                    ret.primary_key = true;
+                } else if meta.path.is_ident("partition_key") {
+                    ret.partition_key = true;
+                } else if meta.path.is_ident("auto_increment") {
+                    ret.auto_increment = true;
+                } else if meta.path.is_ident("not_null") {
+                    ret.not_null = true;
+                } else if meta.path.is_ident("dimension") {
+                    ret.dimension = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                } else if meta.path.is_ident("field_type") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    ret.field_type = Some((lit.value().to_uppercase(), lit.span()));
+                } else if meta.path.is_ident("index") {
+                    let span = meta.path.span();
+                    let mut index = IndexAttr::default();
+                    if meta.input.peek(syn::token::Paren) {
+                        meta.parse_nested_meta(|inner| index_attr(&mut index, &inner))?;
+                    }
+                    ret.indexes.push((index, span));
+                } else {
+                    return Err(meta.error("unknown mochow attribute"));
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok(ret)
+}
+
+fn index_attr(index: &mut IndexAttr, meta: &ParseNestedMeta) -> syn::Result<()> {
+    let str_value = |meta: &ParseNestedMeta| -> syn::Result<String> {
+        Ok(meta.value()?.parse::<LitStr>()?.value().to_uppercase())
+    };
+    let int_value = |meta: &ParseNestedMeta| -> syn::Result<u32> {
+        meta.value()?.parse::<LitInt>()?.base10_parse()
+    };
+    if meta.path.is_ident("name") {
+        index.name = Some(meta.value()?.parse::<LitStr>()?.value());
+    } else if meta.path.is_ident("index_type") {
+        index.index_type = Some(str_value(meta)?);
+    } else if meta.path.is_ident("metric_type") {
+        index.metric_type = Some(str_value(meta)?);
+    } else if meta.path.is_ident("m") {
+        index.m = Some(int_value(meta)?);
+    } else if meta.path.is_ident("ef_construction") {
+        index.ef_construction = Some(int_value(meta)?);
+    } else if meta.path.is_ident("nsq") {
+        index.nsq = Some(int_value(meta)?);
+    } else if meta.path.is_ident("sample_rate") {
+        index.sample_rate = Some(meta.value()?.parse::<LitFloat>()?.base10_parse()?);
+    } else if meta.path.is_ident("coarse_cluster_count") {
+        index.coarse_cluster_count = Some(int_value(meta)?);
+    } else if meta.path.is_ident("fine_cluster_count") {
+        index.fine_cluster_count = Some(int_value(meta)?);
+    } else if meta.path.is_ident("auto_build") {
+        index.auto_build = if meta.input.peek(syn::Token![=]) {
+            meta.value()?.parse::<LitBool>()?.value
+        } else {
+            true
+        };
+    } else {
+        return Err(meta.error("unknown index attribute"));
+    }
+    Ok(())
+}
+
+fn apply_rename_rule(name: &str, rule: &str, span: Span) -> syn::Result<String> {
+    let words: Vec<&str> = name.split('_').filter(|w| !w.is_empty()).collect();
+    let capitalize = |w: &str| {
+        let mut chars = w.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+    let pascal: String = words.iter().map(|w| capitalize(w)).collect();
+    Ok(match rule {
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "PascalCase" => pascal,
+        "camelCase" => {
+            let mut chars = pascal.chars();
+            chars
+                .next()
+                .map(|c| c.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        "snake_case" => name.to_string(),
+        "SCREAMING_SNAKE_CASE" => name.to_uppercase(),
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.replace('_', "-").to_uppercase(),
+        other => {
+            return Err(syn::Error::new(
+                span,
+                format!("unknown rename_all rule {}", other),
+            ))
+        }
+    })
+}
+
+// the inner type of Option<T>, or the type itself
+fn unwrap_option(ty: &Type) -> &Type {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let Some(inner) = generic_arg(&segment.arguments) {
+                    return inner;
+                }
+            }
+        }
+    }
+    ty
+}
+
+fn generic_arg(arguments: &PathArguments) -> Option<&Type> {
+    match arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn infer_field_type(ty: &Type) -> Option<String> {
+    let ty = unwrap_option(ty);
+    let path = match ty {
+        Type::Reference(reference) => return infer_field_type(&reference.elem),
+        Type::Path(path) => path,
+        _ => return None,
+    };
+    let segment = path.path.segments.last()?;
+    let variant = match segment.ident.to_string().as_str() {
+        "bool" => "BOOL",
+        "i8" => "INT8",
+        "u8" => "UINT8",
+        "i16" => "Int16",
+        "u16" => "Uint16",
+        "i32" => "INT32",
+        "u32" => "UINT32",
+        "i64" => "INT64",
+        "u64" => "UINT64",
+        "f32" => "FLOAT",
+        "f64" => "DOUBLE",
+        "String" | "str" => "STRING",
+        "Vec" => {
+            let inner = generic_arg(&segment.arguments)?;
+            match inner {
+                Type::Path(inner) if inner.path.is_ident("f32") || inner.path.is_ident("f64") => {
+                    "FLOAT_VECTOR"
+                }
+                Type::Path(inner) if inner.path.is_ident("u8") => "BINARY",
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(variant.to_string())
+}
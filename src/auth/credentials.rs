@@ -42,10 +42,6 @@ impl Credentials {
             token,
         })
     }
-
-    pub fn to_string(&self) -> String {
-        format!("{}", self)
-    }
 }
 
 impl Display for Credentials {
@@ -1,6 +1,9 @@
 use std::{error::Error, fmt::Display};
 
-use crate::mochow::{api::*, client::*, config::*};
+use crate::mochow::{
+    api::*, client::*, config::*, rebuild::RebuildTableArgsBuilderError,
+    validation::ValidationError,
+};
 
 #[derive(Debug)]
 pub enum SdkError {
@@ -16,6 +19,24 @@ pub enum SdkError {
     /// Mochow SDK param error.
     ParamsError(String),
 
+    /// Request is not sent in dry run mode.
+    DryRunError(String),
+
+    /// Mutating request is rejected by a read only client.
+    ReadOnlyError(String),
+
+    /// Destructive operation on a protected object without confirmation.
+    ConfirmationRequired(String),
+
+    /// Waiting for a table or index is timed out.
+    TimeoutError(String),
+
+    /// A table or index is in a state it can't recover from while waiting.
+    InvalidStateError(String),
+
+    /// Args are rejected by client side validation, with all the violations.
+    ValidationError(ValidationError),
+
     /// Other error.
     OtherError(anyhow::Error),
 }
@@ -28,6 +49,12 @@ impl Display for SdkError {
             SdkError::ServiceError(e) => write!(f, "service Error: {}", e),
             SdkError::OtherError(e) => write!(f, "other error: {}. \ndetail {:?}", e, e),
             SdkError::ParamsError(e) => write!(f, "params error: {}", e),
+            SdkError::DryRunError(e) => write!(f, "dry run error: {}", e),
+            SdkError::ReadOnlyError(e) => write!(f, "read only error: {}", e),
+            SdkError::ConfirmationRequired(e) => write!(f, "confirmation required: {}", e),
+            SdkError::ValidationError(e) => write!(f, "validation error: {}", e),
+            SdkError::TimeoutError(e) => write!(f, "timeout error: {}", e),
+            SdkError::InvalidStateError(e) => write!(f, "invalid state error: {}", e),
             SdkError::RequestError(e) => {
                 write!(f, "request error: {}. \ndetail {:?}", e, e)
             }
@@ -38,6 +65,12 @@ impl Display for SdkError {
     }
 }
 
+impl From<ValidationError> for SdkError {
+    fn from(value: ValidationError) -> Self {
+        SdkError::ValidationError(value)
+    }
+}
+
 impl From<reqwest::Error> for SdkError {
     fn from(value: reqwest::Error) -> Self {
         SdkError::OtherError(value.into())
@@ -133,3 +166,75 @@ impl From<DeleteIndexArgsBuilderError> for SdkError {
         SdkError::OtherError(value.into())
     }
 }
+
+impl From<InsertRowArgsBuilderError> for SdkError {
+    fn from(value: InsertRowArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<UpsertRowArgsBuilderError> for SdkError {
+    fn from(value: UpsertRowArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<DeleteRowArgsBuilderError> for SdkError {
+    fn from(value: DeleteRowArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<QueryRowArgsBuilderError> for SdkError {
+    fn from(value: QueryRowArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<SearchRowsArgsBuilderError> for SdkError {
+    fn from(value: SearchRowsArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<SelectRowsArgsBuilderError> for SdkError {
+    fn from(value: SelectRowsArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<AddFieldArgsBuilderError> for SdkError {
+    fn from(value: AddFieldArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<CreateIndexArgsBuilderError> for SdkError {
+    fn from(value: CreateIndexArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<ModifyIndexArgsBuilderError> for SdkError {
+    fn from(value: ModifyIndexArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<CreateTableArgsBuilderError> for SdkError {
+    fn from(value: CreateTableArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<RawRequestBuilderError> for SdkError {
+    fn from(value: RawRequestBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
+
+impl From<RebuildTableArgsBuilderError> for SdkError {
+    fn from(value: RebuildTableArgsBuilderError) -> Self {
+        SdkError::OtherError(value.into())
+    }
+}
@@ -23,7 +23,7 @@
 //!
 //! #[tokio::main]
 //! async fn main() {
-//! 	let client = mochow::client::MochowClient::new(ACCOUNT, PASSWORD, ENDPOINT).unwrap();
+//!     let client = mochow::client::MochowClient::new(ACCOUNT, PASSWORD, ENDPOINT).unwrap();
 //!
 //!     let _create_database_resp = client.create_database("book").await.unwrap();
 //!
@@ -35,7 +35,12 @@
 //! }
 //! ```
 //!
+// allow the derive macros to refer to this crate by name inside the crate
+extern crate self as mochow_sdk_rust;
+
 pub mod error;
 pub mod mochow;
 
+pub use reqwest_middleware;
+
 mod auth;
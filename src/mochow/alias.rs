@@ -0,0 +1,185 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+alias management on top of the aliases of described tables
+ */
+use std::collections::HashMap;
+
+use crate::error::SdkError;
+
+use super::{api::ServerErrorCode, client::MochowClient};
+
+impl MochowClient {
+    /// aliases of all tables in the database, mapping alias to table,
+    /// every table is described as the server has no api to list aliases,
+    /// tables dropped during the listing are skipped
+    pub async fn list_aliases(&self, data_base: &str) -> Result<HashMap<String, String>, SdkError> {
+        let mut aliases = HashMap::new();
+        for table in self.list_table(data_base).await?.tables {
+            for alias in self.table_aliases(data_base, &table).await? {
+                aliases.insert(alias, table.clone());
+            }
+        }
+        Ok(aliases)
+    }
+
+    /// the table the alias points to, none if there is no such alias,
+    /// tables are described until the alias is found
+    pub async fn resolve_alias(
+        &self,
+        data_base: &str,
+        alias: &str,
+    ) -> Result<Option<String>, SdkError> {
+        for table in self.list_table(data_base).await?.tables {
+            if self
+                .table_aliases(data_base, &table)
+                .await?
+                .iter()
+                .any(|x| x == alias)
+            {
+                return Ok(Some(table));
+            }
+        }
+        Ok(None)
+    }
+
+    // aliases of the table, empty if it's dropped after listed
+    async fn table_aliases(&self, data_base: &str, table: &str) -> Result<Vec<String>, SdkError> {
+        match self.desc_table(data_base, table).await {
+            Ok(resp) => Ok(resp.table.aliases),
+            Err(SdkError::ServiceError(e)) if e.server_code == ServerErrorCode::TABLE_NOT_EXIST => {
+                Ok(vec![])
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// point the alias to the new table, the alias is created if it does not exist,
+    /// it's removed from the current table first and restored if aliasing the new table fails
+    /// so the alias is missing in between and it's not atomic
+    pub async fn move_alias(
+        &self,
+        data_base: &str,
+        alias: &str,
+        new_table: &str,
+    ) -> Result<(), SdkError> {
+        let current = self.resolve_alias(data_base, alias).await?;
+        if current.as_deref() == Some(new_table) {
+            return Ok(());
+        }
+        if let Some(current) = &current {
+            self.unalias_table(data_base, current, alias).await?;
+        }
+        let Err(e) = self.alias_table(data_base, new_table, alias).await else {
+            return Ok(());
+        };
+        if let Some(current) = &current {
+            if let Err(rollback) = self.alias_table(data_base, current, alias).await {
+                return Err(SdkError::InvalidStateError(format!(
+                    "alias {} to table {} failed: {}, and restoring it to table {} failed: {}",
+                    alias, new_table, e, current, rollback
+                )));
+            }
+        }
+        Err(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::mochow::{
+        api::*,
+        config::ClientConfigurationBuilder,
+        middleware::{Fault, FaultInjectionMiddleware, FaultRuleBuilder, FaultSchedule},
+        testing::{create_table_args, field, FakeMochowServer},
+    };
+
+    #[tokio::test]
+    async fn test_aliases() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        client.create_database("book").await?;
+        for table in ["segments_v1", "segments_v2"] {
+            let args = create_table_args(table, vec![field("id", FieldType::STRING)], vec![]);
+            client.create_table(&args).await?;
+        }
+        assert!(client.list_aliases("book").await?.is_empty());
+        assert_eq!(client.resolve_alias("book", "segments").await?, None);
+
+        client.move_alias("book", "segments", "segments_v1").await?;
+        client.alias_table("book", "segments_v1", "latest").await?;
+        assert_eq!(
+            client.list_aliases("book").await?,
+            HashMap::from([
+                ("segments".to_string(), "segments_v1".to_string()),
+                ("latest".to_string(), "segments_v1".to_string()),
+            ])
+        );
+
+        client.move_alias("book", "segments", "segments_v2").await?;
+        assert_eq!(
+            client.resolve_alias("book", "segments").await?.as_deref(),
+            Some("segments_v2")
+        );
+        client.move_alias("book", "segments", "segments_v2").await?;
+
+        // the alias is restored if the new table can't be aliased
+        assert!(client
+            .move_alias("book", "segments", "segments_v3")
+            .await
+            .is_err());
+        assert_eq!(
+            client.resolve_alias("book", "segments").await?.as_deref(),
+            Some("segments_v2")
+        );
+
+        // segments_v1 is dropped after listed, and resolving stops at the first match
+        let faults = FaultInjectionMiddleware::new(0)
+            .with_rule(
+                FaultRuleBuilder::default()
+                    .fault(Fault::ServerCode(404, ServerErrorCode::TABLE_NOT_EXIST))
+                    .schedule(FaultSchedule::Sequence(vec![true, false, false]))
+                    .path("/v1/table?desc")
+                    .build()?,
+            )
+            .with_rule(
+                FaultRuleBuilder::default()
+                    .fault(Fault::ServerCode(400, ServerErrorCode::INTERNAL_ERROR))
+                    .schedule(FaultSchedule::Sequence(vec![false, false, false, true]))
+                    .path("/v1/table?desc")
+                    .build()?,
+            );
+        let config = ClientConfigurationBuilder::default()
+            .account("root")
+            .api_key("fake_api_key")
+            .endpoint(server.endpoint())
+            .build()?;
+        let client = MochowClient::new_with_middleware(&config, vec![Arc::new(faults)])?;
+        assert_eq!(
+            client.list_aliases("book").await?,
+            HashMap::from([("segments".to_string(), "segments_v2".to_string())])
+        );
+        assert_eq!(
+            client.resolve_alias("book", "latest").await?.as_deref(),
+            Some("segments_v1")
+        );
+        Ok(())
+    }
+}
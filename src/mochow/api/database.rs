@@ -71,6 +71,10 @@ impl IntoRequest for DropDatabaseArgs {
         let url = format!("{}/{}/database", config.endpoint, config.version);
         client.delete(url).json(&self)
     }
+
+    fn protected_target(&self) -> Option<(String, Option<String>)> {
+        Some((self.database.clone(), None))
+    }
 }
 
 impl IntoRequest for ListDatabaseArgs {
@@ -82,6 +86,10 @@ impl IntoRequest for ListDatabaseArgs {
         let url = format!("{}/{}/database?list", config.endpoint, config.version);
         client.post(url)
     }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -91,7 +99,7 @@ mod tests {
 
     use super::*;
 
-    use crate::mochow::{TESTDATABSE, UTCLIENT};
+    use crate::mochow::{test_client, testing::FakeMochowServer, TESTDATABSE, TESTTABLE};
 
     #[test]
     fn create_database_param_serialize() -> Result<()> {
@@ -121,14 +129,16 @@ mod tests {
                 ]
             }
         "#;
-        let v: ListDatabaseResponse = serde_json::from_str(&data)?;
+        let v: ListDatabaseResponse = serde_json::from_str(data)?;
         println!("{:?}", v);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_create_database() -> Result<()> {
-        let _ret = UTCLIENT.create_database(&TESTDATABSE).await;
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        let _ret = client.create_database(&TESTDATABSE).await;
         match _ret {
             Ok(res) => {
                 println!("{:?}", res);
@@ -152,14 +162,18 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_database() -> Result<()> {
-        let ret = UTCLIENT.list_database().await?;
+        let (_server, client) = test_client().await?;
+        let ret = client.list_database().await?;
         println!("{:?}", ret);
+        assert_eq!(ret.databases, vec![TESTDATABSE.to_string()]);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_drop_database() -> Result<()> {
-        let ret = UTCLIENT.drop_database(&TESTDATABSE).await?;
+        let (_server, client) = test_client().await?;
+        client.drop_table(&TESTDATABSE, &TESTTABLE).await?;
+        let ret = client.drop_database(&TESTDATABSE).await?;
         println!("{:?}", ret);
         Ok(())
     }
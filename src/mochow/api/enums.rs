@@ -14,7 +14,7 @@
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AutoBuildPolicyType {
     #[serde(rename = "TIMING", alias = "timing")]
     TIMING,
@@ -24,7 +24,7 @@ pub enum AutoBuildPolicyType {
     ROW_COUNT_INCREMENT,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FieldType {
     #[serde(rename = "BOOL")]
     BOOL,
@@ -70,7 +70,7 @@ pub enum FieldType {
     FLOAT_VECTOR,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IndexState {
     #[serde(rename = "INVALID")]
     INVALID,
@@ -80,7 +80,7 @@ pub enum IndexState {
     NORMAL,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TableState {
     #[serde(rename = "INVALID")]
     INVALID,
@@ -92,7 +92,7 @@ pub enum TableState {
     DELETING,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IndexType {
     #[serde(rename = "FLAT")]
     FLAT,
@@ -106,7 +106,7 @@ pub enum IndexType {
     SECONDARY_INDEX,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MetricType {
     #[serde(rename = "L2")]
     L2,
@@ -116,14 +116,14 @@ pub enum MetricType {
     COSINE,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PartitionType {
     #[default]
     #[serde(rename = "HASH")]
     HASH,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ReadConsistency {
     #[default]
     #[serde(rename = "EVENTUAL")]
@@ -132,7 +132,7 @@ pub enum ReadConsistency {
     STRONG,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ServerErrorCode {
     UNKNOWN,
     INTERNAL_ERROR = 1,
@@ -14,7 +14,7 @@
 
 use derive_builder::Builder;
 use reqwest_middleware::ClientWithMiddleware;
-use serde::{Deserialize, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
 
 use crate::mochow::{client::IntoRequest, config::ClientConfiguration};
 
@@ -37,8 +37,8 @@ pub struct CreateIndexArgs {
     pub indexes: Vec<IndexSchema>,
 }
 
-/// index param
-#[derive(Debug, Clone, Builder, Serialize, Deserialize)]
+/// index param, params are deserialized by the index type
+#[derive(Debug, Clone, Builder, Serialize)]
 pub struct IndexSchema {
     #[builder(default, setter(into))]
     #[serde(default, rename = "indexName")]
@@ -102,6 +102,71 @@ pub struct IndexSchema {
     pub index_major_version: Option<u64>,
 }
 
+impl<'de> Deserialize<'de> for IndexSchema {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // the same fields with params left as json until the index type is known
+        #[derive(Deserialize)]
+        struct RawIndexSchema {
+            #[serde(default, rename = "indexName")]
+            index_name: String,
+            #[serde(default, rename = "indexType")]
+            index_type: Option<IndexType>,
+            #[serde(default, rename = "metricType")]
+            metric_type: Option<MetricType>,
+            #[serde(default)]
+            params: Option<serde_json::Value>,
+            #[serde(default)]
+            field: String,
+            #[serde(default, rename = "autoBuild")]
+            auto_build: bool,
+            #[serde(default)]
+            state: Option<IndexState>,
+            #[serde(default, rename = "autoBuildPolicy")]
+            auto_build_policy: Option<AutoBuildPolicy>,
+            #[serde(default, rename = "indexMajorVersion")]
+            index_major_version: Option<u64>,
+        }
+
+        let raw = RawIndexSchema::deserialize(deserializer)?;
+        // empty params of the index types without params are the same as none
+        let no_params = matches!(
+            raw.index_type,
+            Some(IndexType::FLAT | IndexType::SECONDARY_INDEX)
+        );
+        let is_empty = |params: &serde_json::Value| {
+            params.is_null() || (no_params && params.as_object().is_some_and(|obj| obj.is_empty()))
+        };
+        let params = raw
+            .params
+            .filter(|params| !is_empty(params))
+            .map(|params| VectorIndexParams::from_value(raw.index_type.as_ref(), params))
+            .transpose()
+            .map_err(D::Error::custom)?;
+        Ok(IndexSchema {
+            index_name: raw.index_name,
+            index_type: raw.index_type,
+            metric_type: raw.metric_type,
+            params,
+            field: raw.field,
+            auto_build: raw.auto_build,
+            state: raw.state,
+            auto_build_policy: raw.auto_build_policy,
+            index_major_version: raw.index_major_version,
+        })
+    }
+}
+
+impl IndexSchema {
+    /// the index without server side state, to create an index with the same definition
+    pub fn without_server_fields(&self) -> IndexSchema {
+        IndexSchema {
+            state: None,
+            index_major_version: None,
+            ..self.clone()
+        }
+    }
+}
+
 ///  auto build index strategy
 #[derive(Debug, Clone, Builder, Default, Serialize, Deserialize)]
 pub struct AutoBuildPolicy {
@@ -141,6 +206,8 @@ pub struct AutoBuildPolicy {
     pub row_count_increment_ratio: f64,
 }
 
+/// params of vector index, untagged as the api,
+/// in [IndexSchema] they are deserialized by the index type rather than the shape
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum VectorIndexParams {
@@ -150,9 +217,45 @@ pub enum VectorIndexParams {
     HNSW(HNSWIndexParam),
     /// PUCK index
     PUCK(PUCKIndexParam),
+    /// FLAT index, which has no params
+    FLAT(FLATIndexParam),
+    /// params of other index types, kept as they are
+    Raw(serde_json::Value),
+}
+
+impl VectorIndexParams {
+    /// decode the params of the index type, params of unknown index type or with fields
+    /// unknown to the sdk are kept raw, so they are sent back as they are
+    pub fn from_value(
+        index_type: Option<&IndexType>,
+        value: serde_json::Value,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(match index_type {
+            Some(IndexType::HNSW) => Self::typed_or_raw(value, VectorIndexParams::HNSW),
+            Some(IndexType::HNSWPQ) => Self::typed_or_raw(value, VectorIndexParams::HNSWPQ),
+            Some(IndexType::PUCK) => Self::typed_or_raw(value, VectorIndexParams::PUCK),
+            Some(IndexType::FLAT) => Self::typed_or_raw(value, VectorIndexParams::FLAT),
+            _ => VectorIndexParams::Raw(value),
+        })
+    }
+
+    fn typed_or_raw<T: serde::de::DeserializeOwned>(
+        value: serde_json::Value,
+        typed: fn(T) -> Self,
+    ) -> Self {
+        match serde_json::from_value(value.clone()) {
+            Ok(params) => typed(params),
+            Err(_) => VectorIndexParams::Raw(value),
+        }
+    }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FLATIndexParam {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct HNSWIndexParam {
     /// range \[4, 128\],
     /// neighboring nodes are connected to each node in the HNSW index graph
@@ -165,6 +268,7 @@ pub struct HNSWIndexParam {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct HNSWPQIndexParam {
     /// range \[4, 128\],
     /// neighboring nodes are connected to each node in the HNSW index graph
@@ -187,6 +291,7 @@ pub struct HNSWPQIndexParam {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct PUCKIndexParam {
     /// range \[1, 5000\],
     /// the number of coarse cluster centers in the index
@@ -281,6 +386,10 @@ impl IntoRequest for DescriptIndexArgs {
         let url = format!("{}/{}/index?desc", config.endpoint, config.version);
         client.post(url).json(&self)
     }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
 }
 
 impl IntoRequest for RebuildIndexArgs {
@@ -303,6 +412,10 @@ impl IntoRequest for DeleteIndexArgs {
         let url = format!("{}/{}/index", config.endpoint, config.version);
         client.delete(url).json(&self)
     }
+
+    fn protected_target(&self) -> Option<(String, Option<String>)> {
+        Some((self.database.clone(), Some(self.table.clone())))
+    }
 }
 
 impl IntoRequest for ModifyIndexArgs {
@@ -320,12 +433,68 @@ impl IntoRequest for ModifyIndexArgs {
 mod tests {
     use anyhow::Result;
 
-    use crate::mochow::{TESTDATABSE, TESTTABLE, UTCLIENT};
+    use crate::mochow::{test_client, TESTDATABSE, TESTTABLE};
 
     use super::*;
+
+    #[test]
+    fn test_deserialize_index_params() -> Result<()> {
+        let index = |index_type: &str, params: serde_json::Value| {
+            serde_json::from_value::<IndexSchema>(serde_json::json!({
+                "indexName": "vector_idx", "field": "vector", "indexType": index_type,
+                "metricType": "L2", "params": params,
+            }))
+        };
+        let hnsw = serde_json::json!({"M": 16, "efConstruction": 200});
+        assert!(matches!(
+            index("HNSW", hnsw.clone())?.params,
+            Some(VectorIndexParams::HNSW(_))
+        ));
+        // params not matching the index type are kept raw
+        assert!(matches!(
+            index("HNSWPQ", hnsw.clone())?.params,
+            Some(VectorIndexParams::Raw(_))
+        ));
+        let hnswpq =
+            serde_json::json!({"M": 16, "efConstruction": 200, "NSQ": 4, "sampleRate": 0.5});
+        assert!(matches!(
+            index("HNSWPQ", hnswpq.clone())?.params,
+            Some(VectorIndexParams::HNSWPQ(_))
+        ));
+        // so are the params with fields unknown to the index type, to be sent back losslessly
+        let extended = index("HNSW", hnswpq.clone())?;
+        assert!(matches!(extended.params, Some(VectorIndexParams::Raw(_))));
+        assert_eq!(serde_json::to_value(&extended)?["params"], hnswpq);
+        let puck = serde_json::json!({"coarseClusterCount": 5, "fineClusterCount": 5});
+        assert!(matches!(
+            index("PUCK", puck)?.params,
+            Some(VectorIndexParams::PUCK(_))
+        ));
+        // empty params of the index types without params are none
+        assert!(index("FLAT", serde_json::json!({}))?.params.is_none());
+        let secondary = serde_json::from_value::<IndexSchema>(serde_json::json!({
+            "indexName": "page_idx", "field": "page", "indexType": "SECONDARY", "params": {},
+        }))?;
+        assert!(secondary.params.is_none());
+        let mut violations = crate::mochow::validation::Violations::default();
+        secondary.check(&mut violations, "index", None);
+        assert!(violations.into_result().is_ok());
+        assert!(index("FLAT", serde_json::Value::Null)?.params.is_none());
+        let raw = serde_json::json!({"nlist": 128});
+        let flat = index("FLAT", raw.clone())?;
+        assert!(matches!(flat.params, Some(VectorIndexParams::Raw(_))));
+        assert_eq!(serde_json::to_value(&flat)?["params"], raw);
+        assert!(matches!(
+            index("SECONDARY", raw)?.params,
+            Some(VectorIndexParams::Raw(_))
+        ));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_rebuild_index() -> Result<()> {
-        let ret = UTCLIENT
+        let (_server, client) = test_client().await?;
+        let ret = client
             .rebuild_index(&TESTDATABSE, &TESTTABLE, "vector_idx")
             .await?;
         println!("rebuild index: {:?}", ret);
@@ -334,8 +503,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_desc_index() -> Result<()> {
-        let ret = UTCLIENT
-            .desc_index(&TESTDATABSE, &TESTTABLE, "vector_idx_hnswpq")
+        let (_server, client) = test_client().await?;
+        let ret = client
+            .desc_index(&TESTDATABSE, &TESTTABLE, "vector_idx")
             .await?;
         println!("desc index: {:?}", ret);
         Ok(())
@@ -343,8 +513,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_index() -> Result<()> {
-        let ret = UTCLIENT
-            .delete_index(&TESTDATABSE, &TESTTABLE, "vector_idx_hnswpq")
+        let (_server, client) = test_client().await?;
+        let ret = client
+            .delete_index(&TESTDATABSE, &TESTTABLE, "vector_idx")
             .await?;
         println!("delete index: {:?}", ret);
         Ok(())
@@ -352,6 +523,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_index_flat() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let indexes = vec![IndexSchemaBuilder::default()
             .index_name("vector_idx_flat")
             .field("vector")
@@ -363,14 +535,14 @@ mod tests {
             .table(TESTTABLE.to_string())
             .indexes(indexes)
             .build()?;
-        let ret = UTCLIENT.create_index(&args).await?;
+        let ret = client.create_index(&args).await?;
         println!("create index: {:?}", ret);
         Ok(())
     }
 
-    /// remembder there is no index named 'vertor_idx' in the test table before test
     #[tokio::test]
     async fn test_create_index_hnsw() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let indexes = vec![IndexSchemaBuilder::default()
             .index_name("vector_idx_hnsw")
             .field("vector")
@@ -386,13 +558,14 @@ mod tests {
             .table(TESTTABLE.to_string())
             .indexes(indexes)
             .build()?;
-        let ret = UTCLIENT.create_index(&args).await?;
+        let ret = client.create_index(&args).await?;
         println!("create index: {:?}", ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_create_index_hnswpq() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let indexes = vec![IndexSchemaBuilder::default()
             .index_name("vector_idx_hnswpq")
             .field("vector")
@@ -410,13 +583,14 @@ mod tests {
             .table(TESTTABLE.to_string())
             .indexes(indexes)
             .build()?;
-        let ret = UTCLIENT.create_index(&args).await?;
+        let ret = client.create_index(&args).await?;
         println!("create index: {:?}", ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_create_index_puck() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let indexes = vec![IndexSchemaBuilder::default()
             .index_name("vector_idx_puck")
             .field("vector")
@@ -432,20 +606,21 @@ mod tests {
             .table(TESTTABLE.to_string())
             .indexes(indexes)
             .build()?;
-        let ret = UTCLIENT.create_index(&args).await?;
+        let ret = client.create_index(&args).await?;
         println!("create index: {:?}", ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_modify_index() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let index = IndexSchemaBuilder::default()
             .index_name("vector_idx")
             .auto_build(true)
             .auto_build_policy(
                 AutoBuildPolicyBuilder::default()
                     .policy_type(AutoBuildPolicyType::PERIODICAL)
-                    .period_in_second(5000 as u64)
+                    .period_in_second(5000_u64)
                     .build()?,
             )
             .build()?;
@@ -456,7 +631,7 @@ mod tests {
             .build()?;
         // let data = serde_json::to_string(&args)?;
         // println!("json data: {}", data);
-        let ret = UTCLIENT.modify_index(&args).await?;
+        let ret = client.modify_index(&args).await?;
         println!("modify index: {:?}", ret);
         Ok(())
     }
@@ -477,6 +477,13 @@ impl IntoRequest for DeleteRowArgs {
         let url = format!("{}/{}/row?delete", config.endpoint, config.version);
         client.post(url).json(&self)
     }
+
+    // deleting rows by filter may delete all of them
+    fn protected_target(&self) -> Option<(String, Option<String>)> {
+        self.filter
+            .as_ref()
+            .map(|_| (self.database.clone(), Some(self.table.clone())))
+    }
 }
 
 impl IntoRequest for QueryRowArgs {
@@ -488,6 +495,10 @@ impl IntoRequest for QueryRowArgs {
         let url = format!("{}/{}/row?query", config.endpoint, config.version);
         client.post(url).json(&self)
     }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
 }
 
 impl IntoRequest for SearchRowsArgs {
@@ -499,6 +510,10 @@ impl IntoRequest for SearchRowsArgs {
         let url = format!("{}/{}/row?search", config.endpoint, config.version);
         client.post(url).json(&self)
     }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
 }
 
 impl IntoRequest for SelectRowsArgs {
@@ -510,6 +525,10 @@ impl IntoRequest for SelectRowsArgs {
         let url = format!("{}/{}/row?select", config.endpoint, config.version);
         client.post(url).json(&self)
     }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
 }
 
 impl IntoRequest for BatchSearchRowsArgs {
@@ -521,12 +540,16 @@ impl IntoRequest for BatchSearchRowsArgs {
         let url = format!("{}/{}/row?batchSearch", config.endpoint, config.version);
         client.post(url).json(&self)
     }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mochow::{TESTDATABSE, TESTTABLE, UTCLIENT};
+    use crate::mochow::{test_client, TESTDATABSE, TESTTABLE};
     use anyhow::Result;
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -567,43 +590,45 @@ mod tests {
 
     #[tokio::test]
     async fn test_insert_row() -> Result<()> {
+        let (_server, client) = test_client().await?;
         // insert row with a defined struct
         let args1 = InsertRowArgsBuilder::default()
-            .database(&TESTDATABSE.to_string())
-            .table(&TESTTABLE.to_string())
+            .database(TESTDATABSE.to_string())
+            .table(TESTTABLE.to_string())
             .rows(vec![MyRecord {
-                id: "0001".to_string(),
+                id: "0005".to_string(),
                 book_name: "西游记".to_string(),
                 author: "吴承恩".to_string(),
                 page: 21,
                 vector: vec![0.2123, 0.24, 0.213],
             }])
             .build()?;
-        let _ret = UTCLIENT.insert_row(&args1).await?;
+        let _ret = client.insert_row(&args1).await?;
         println!("insert_row ret: {:?}", _ret);
 
         // insert row with a json object
         let args2 = InsertRowArgsBuilder::default()
-            .database(&TESTDATABSE.to_string())
-            .table(&TESTTABLE.to_string())
+            .database(TESTDATABSE.to_string())
+            .table(TESTTABLE.to_string())
             .rows(vec![serde_json::json!({
-                "id": "0002",
+                "id": "0006",
                 "bookName": "西游记",
                 "author": "吴承恩",
                 "page": 22,
                 "vector": [0.2123, 0.24, 0.213],
             })])
             .build()?;
-        let _ret = UTCLIENT.insert_row(&args2).await?;
+        let _ret = client.insert_row(&args2).await?;
         println!("insert_row ret: {:?}", _ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_upsert_row() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let args = UpsertRowArgsBuilder::default()
-            .database(&TESTDATABSE.to_string())
-            .table(&TESTTABLE.to_string())
+            .database(TESTDATABSE.to_string())
+            .table(TESTTABLE.to_string())
             .rows(vec![
                 serde_json::json!({
                     "id":       "0001",
@@ -635,16 +660,17 @@ mod tests {
                 }),
             ])
             .build()?;
-        let _ret = UTCLIENT.upsert_row(&args).await?;
+        let _ret = client.upsert_row(&args).await?;
         println!("upsert_row ret: {:?}", _ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_update_row() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let args = UpdateRowArgsBuilder::default()
-            .database(&TESTDATABSE.to_string())
-            .table(&TESTTABLE.to_string())
+            .database(TESTDATABSE.to_string())
+            .table(TESTTABLE.to_string())
             .primary_key(serde_json::json!({
                 "id": "0001",
             }))
@@ -654,41 +680,43 @@ mod tests {
                 "page":     100,
             }))
             .build()?;
-        let _ret = UTCLIENT.update_row(&args).await?;
+        let _ret = client.update_row(&args).await?;
         println!("update_row ret: {:?}", _ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_delete_row() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let args = DeleteRowArgsBuilder::default()
-            .database(&TESTDATABSE.to_string())
-            .table(&TESTTABLE.to_string())
+            .database(TESTDATABSE.to_string())
+            .table(TESTTABLE.to_string())
             // .primary_key(serde_json::json!({
             //     "id": "0001",
             // }))
             .filter("page >= 22")
             .build()?;
-        let _ret = UTCLIENT.delete_rows(&args).await?;
+        let _ret = client.delete_rows(&args).await?;
         println!("delete_row ret: {:?}", _ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_query_row() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let args = QueryRowArgsBuilder::default()
-            .database(&TESTDATABSE.to_string())
-            .table(&TESTTABLE.to_string())
+            .database(TESTDATABSE.to_string())
+            .table(TESTTABLE.to_string())
             .primary_key(serde_json::json!({
                 "id": "0001",
             }))
             .projections(vec!["id".to_string(), "bookName".to_string()])
             .retrieve_vector(false)
             .build()?;
-        let query_ret: QueryRowsResponse<MyRecord> = UTCLIENT.query_row(&args).await?;
+        let query_ret: QueryRowsResponse<MyRecord> = client.query_row(&args).await?;
         println!("query_row ret: {:?}", query_ret.row);
         let row1 = query_ret.row;
-        let query_ret: QueryRowsResponse<serde_json::Value> = UTCLIENT.query_row(&args).await?;
+        let query_ret: QueryRowsResponse<serde_json::Value> = client.query_row(&args).await?;
         println!("query_row ret: {:?}", query_ret.row);
         // convert json value to struct
         let row2 = serde_json::from_value(query_ret.row)?;
@@ -698,19 +726,20 @@ mod tests {
 
     #[tokio::test]
     async fn test_select_row() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let mut args = SelectRowsArgsBuilder::default()
-            .database(&TESTDATABSE.to_string())
-            .table(&TESTTABLE.to_string())
+            .database(TESTDATABSE.to_string())
+            .table(TESTTABLE.to_string())
             .projections(vec![
                 "id".to_string(),
                 "bookName".to_string(),
                 "page".to_string(),
             ])
             .filter("page > 21")
-            .limit(1 as u32)
+            .limit(1_u32)
             .build()?;
         loop {
-            let ret: SelectRowsResponse<serde_json::Value> = UTCLIENT.select_rows(&args).await?;
+            let ret: SelectRowsResponse<serde_json::Value> = client.select_rows(&args).await?;
             println!("select_rows ret: {:?}", ret);
             if !ret.is_truncated {
                 break;
@@ -724,9 +753,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_search() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let search_args = SearchRowsArgsBuilder::default()
-            .database(&TESTDATABSE.to_string())
-            .table(&TESTTABLE.to_string())
+            .database(TESTDATABSE.to_string())
+            .table(TESTTABLE.to_string())
             .anns(
                 AnnsSearchParamsBuilder::default()
                     .vector_field("vector")
@@ -743,13 +773,14 @@ mod tests {
             )
             .retrieve_vector(true)
             .build()?;
-        let ret: SearchRowsResponse<serde_json::Value> = UTCLIENT.search_rows(&search_args).await?;
+        let ret: SearchRowsResponse<serde_json::Value> = client.search_rows(&search_args).await?;
         println!("search_rows ret: {:?}", ret.rows);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_batch_search() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let batch_ann_params = BatchAnnsSearchParamsBuilder::default()
             .vector_field("vector")
             .vector_floats(vec![vec![0.3123, 0.43, 0.213], vec![0.5512, 0.33, 0.43]])
@@ -763,13 +794,13 @@ mod tests {
             .filter("bookName = '三国演义'")
             .build()?;
         let batch_search_args = BatchSearchRowsArgsBuilder::default()
-            .database(&TESTDATABSE.to_string())
-            .table(&TESTTABLE.to_string())
+            .database(TESTDATABSE.to_string())
+            .table(TESTTABLE.to_string())
             .anns(batch_ann_params)
             .retrieve_vector(true)
             .build()?;
         let batch_rets: BatchSearchRowsResponse<serde_json::Value> =
-            UTCLIENT.batch_search_rows(&batch_search_args).await?;
+            client.batch_search_rows(&batch_search_args).await?;
         for (i, bs) in batch_rets.results.iter().enumerate() {
             println!("batch: {}, {:?}", i, bs.search_vector_floats);
             for (j, ss) in bs.rows.iter().enumerate() {
@@ -18,10 +18,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::mochow::{client::IntoRequest, config::ClientConfiguration};
 
-use super::{FieldType, IndexSchema, PartitionType, TableState};
+use super::{CreateIndexArgs, FieldType, IndexSchema, PartitionType, TableState};
 
 /// click <https://cloud.baidu.com/doc/VDB/s/flrsob0zr> for more details
-
 /**
  * create table args, response with [crate::mochow::api::CommonResponse]
  */
@@ -84,6 +83,38 @@ pub struct TableSchema {
     pub indexes: Vec<IndexSchema>,
 }
 
+/// a row struct with the table schema, usually derived by `#[derive(MochowTable)]`
+/// ```rust
+/// use mochow_sdk_rust::mochow::api::MochowTable;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, MochowTable)]
+/// struct Book {
+///     #[mochow(primary_key, partition_key, not_null)]
+///     id: String,
+///     #[serde(rename = "bookName")]
+///     #[mochow(not_null, index(name = "book_name_idx"))]
+///     book_name: String,
+///     page: Option<u32>,
+///     #[mochow(not_null, dimension = 3)]
+///     #[mochow(index(index_type = "HNSW", metric_type = "L2", m = 32, ef_construction = 200))]
+///     vector: Vec<f32>,
+/// }
+///
+/// let schema = Book::table_schema();
+/// assert_eq!(schema.fields[1].field_name, "bookName");
+/// assert_eq!(Book::projections(), vec!["id", "bookName", "page", "vector"]);
+/// ```
+pub trait MochowTable {
+    /// schema of the table, with fields and indexes
+    fn table_schema() -> TableSchema;
+
+    /// names of all the fields, used as projections of query, search and select
+    fn projections() -> Vec<String>;
+}
+
+pub use mochow_sdk_derive::MochowTable;
+
 #[derive(Debug, Clone, Builder, Serialize, Deserialize)]
 pub struct FieldSchema {
     /// must start with a letter,
@@ -198,6 +229,54 @@ pub struct DescriptTableResponse {
     pub table: DescriptTable,
 }
 
+impl TableSchema {
+    /// the schema without server side state like index state and major version,
+    /// to create a table or indexes with the same schema
+    pub fn without_server_fields(&self) -> TableSchema {
+        TableSchema {
+            fields: self.fields.clone(),
+            indexes: self
+                .indexes
+                .iter()
+                .map(IndexSchema::without_server_fields)
+                .collect(),
+        }
+    }
+}
+
+impl DescriptTable {
+    /// args to create the indexes of the table, like on a copy of the table
+    pub fn to_create_index_args(&self) -> CreateIndexArgs {
+        CreateIndexArgs {
+            database: self.database.clone(),
+            table: self.table.clone(),
+            indexes: self.schema.without_server_fields().indexes,
+        }
+    }
+}
+
+/// args to create a table with the same definition, like in another cluster,
+/// server side fields like state, aliases, create time and index state are stripped
+impl From<DescriptTable> for CreateTableArgs {
+    fn from(table: DescriptTable) -> Self {
+        CreateTableArgs {
+            schema: table.schema.without_server_fields(),
+            database: table.database,
+            table: table.table,
+            description: Some(table.description),
+            replication: table.replication,
+            partition: table.partition,
+            enable_dynamic_field: Some(table.enable_dynamic_field),
+        }
+    }
+}
+
+impl From<DescriptTableResponse> for CreateTableArgs {
+    fn from(resp: DescriptTableResponse) -> Self {
+        resp.table.into()
+    }
+}
+
 /**
  * add field args, response with [crate::mochow::api::CommonResponse]
  */
@@ -282,6 +361,10 @@ impl IntoRequest for DropTableArgs {
         let url = format!("{}/{}/table", config.endpoint, config.version);
         client.delete(url).json(&self)
     }
+
+    fn protected_target(&self) -> Option<(String, Option<String>)> {
+        Some((self.database.clone(), Some(self.table.clone())))
+    }
 }
 
 impl IntoRequest for ListTableArgs {
@@ -293,6 +376,10 @@ impl IntoRequest for ListTableArgs {
         let url = format!("{}/{}/table?list", config.endpoint, config.version);
         client.post(url).json(&self)
     }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
 }
 
 impl IntoRequest for DescriptTableArgs {
@@ -304,6 +391,10 @@ impl IntoRequest for DescriptTableArgs {
         let url = format!("{}/{}/table?desc", config.endpoint, config.version);
         client.post(url).json(&self)
     }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
 }
 
 impl IntoRequest for AddFieldArgs {
@@ -326,6 +417,10 @@ impl IntoRequest for StatsTableArgs {
         let url = format!("{}/{}/table?stats", config.endpoint, config.version);
         client.post(url).json(&self)
     }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
 }
 
 impl IntoRequest for AliasTableArgs {
@@ -359,7 +454,7 @@ mod tests {
     };
     use super::*;
     use crate::mochow::api::{AutoBuildPolicyType, IndexType, MetricType};
-    use crate::mochow::{TESTDATABSE, TESTTABLE, UTCLIENT};
+    use crate::mochow::{test_client, testing::FakeMochowServer, TESTDATABSE, TESTTABLE};
 
     #[test]
     fn create_table_args_serialize_test() -> Result<()> {
@@ -367,11 +462,11 @@ mod tests {
             .database("test_db")
             .table("test_table")
             .description("this is description".to_string())
-            .replication(3 as u32)
+            .replication(3_u32)
             .enable_dynamic_field(true)
             .partition(
                 PartitionBuilder::default()
-                    .partition_num(1 as u32)
+                    .partition_num(1_u32)
                     .partition_type(PartitionType::HASH)
                     .build()?,
             )
@@ -451,6 +546,162 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn descript_table_to_create_args_test() -> Result<()> {
+        let types = [
+            "BOOL",
+            "INT8",
+            "UINT8",
+            "INT16",
+            "UINT16",
+            "INT32",
+            "UINT32",
+            "INT64",
+            "UINT64",
+            "FLOAT",
+            "DOUBLE",
+            "DATE",
+            "DATETIME",
+            "TIMESTAMP",
+            "STRING",
+            "BINARY",
+            "UUID",
+            "TEXT",
+            "TEXT_GBK",
+            "TEXT_GB18030",
+            "FLOAT_VECTOR",
+        ];
+        let fields: Vec<serde_json::Value> = types
+            .iter()
+            .enumerate()
+            .map(|(i, t)| match *t {
+                "FLOAT_VECTOR" => serde_json::json!({
+                    "fieldName": "vector", "fieldType": t, "primaryKey": false, "partitionKey": false,
+                    "autoIncrement": false, "notNull": true, "dimension": 8,
+                }),
+                _ => serde_json::json!({
+                    "fieldName": format!("f{}", i), "fieldType": t, "primaryKey": *t == "UINT64",
+                    "partitionKey": *t == "UINT64", "autoIncrement": *t == "UINT64", "notNull": false,
+                }),
+            })
+            .collect();
+        let indexes = serde_json::json!([
+            {"indexName": "hnsw_idx", "field": "vector", "indexType": "HNSW", "metricType": "L2",
+             "params": {"M": 16, "efConstruction": 200}, "autoBuild": false},
+            {"indexName": "hnswpq_idx", "field": "vector", "indexType": "HNSWPQ", "metricType": "IP",
+             "params": {"M": 16, "efConstruction": 200, "NSQ": 4, "sampleRate": 0.5}, "autoBuild": true,
+             "autoBuildPolicy": {"policyType": "PERIODICAL", "periodInSecond": 3600,
+                                 "rowCountIncrement": 0, "rowCountIncrementRatio": 0.0}},
+            {"indexName": "puck_idx", "field": "vector", "indexType": "PUCK", "metricType": "COSINE",
+             "params": {"coarseClusterCount": 5, "fineClusterCount": 5}, "autoBuild": false},
+            {"indexName": "flat_idx", "field": "vector", "indexType": "FLAT", "metricType": "L2",
+             "autoBuild": false},
+            // a param unknown to the sdk is kept raw
+            {"indexName": "raw_idx", "field": "vector", "indexType": "HNSW", "metricType": "L2",
+             "params": {"M": 16, "efConstruction": 200, "maxLevel": 4}, "autoBuild": false},
+            {"indexName": "secondary_idx", "field": "f6", "indexType": "SECONDARY", "autoBuild": false},
+        ]);
+        let mut described = indexes.clone();
+        for index in described.as_array_mut().unwrap() {
+            index["state"] = serde_json::json!("NORMAL");
+            index["indexMajorVersion"] = serde_json::json!(3);
+            // the server describes empty params of the index types without params
+            if index.get("params").is_none() {
+                index["params"] = serde_json::json!({});
+            }
+        }
+        let resp: DescriptTableResponse = serde_json::from_value(serde_json::json!({
+            "code": 0,
+            "msg": "Success",
+            "table": {
+                "database": "book", "table": "segments", "createTime": "2024-09-26 10:00:00",
+                "description": "segments", "replication": 3,
+                "partition": {"partitionType": "HASH", "partitionNum": 10},
+                "enableDynamicField": true, "state": "NORMAL", "aliases": ["latest"],
+                "schema": {"fields": fields, "indexes": described},
+            },
+        }))?;
+        let index_args = resp.table.to_create_index_args();
+        assert_eq!(serde_json::to_value(&index_args.indexes)?, indexes);
+        assert!(index_args.indexes[3].params.is_none());
+        assert!(matches!(
+            index_args.indexes[4].params,
+            Some(VectorIndexParams::Raw(_))
+        ));
+        assert!(index_args.indexes[5].params.is_none());
+        index_args.validate(Some(&resp.table.schema))?;
+
+        let args = CreateTableArgs::from(resp);
+        assert_eq!(
+            serde_json::to_value(&args)?,
+            serde_json::json!({
+                "database": "book", "table": "segments", "description": "segments", "replication": 3,
+                "partition": {"partitionType": "HASH", "partitionNum": 10},
+                "enableDynamicField": true,
+                "schema": {"fields": fields, "indexes": indexes},
+            })
+        );
+        args.validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn derive_table_schema_test() -> Result<()> {
+        #[allow(dead_code)]
+        #[derive(Serialize, Deserialize, MochowTable)]
+        #[serde(rename_all = "camelCase")]
+        struct Segment {
+            #[mochow(primary_key, partition_key, auto_increment, not_null)]
+            segment_id: u64,
+            #[mochow(not_null, index)]
+            book_name: String,
+            #[serde(rename = "text")]
+            #[mochow(field_type = "TEXT")]
+            content: Option<String>,
+            #[serde(skip)]
+            cached: bool,
+            #[mochow(not_null, dimension = 4)]
+            #[mochow(index(
+                name = "vector_idx",
+                index_type = "HNSWPQ",
+                metric_type = "COSINE",
+                m = 16,
+                ef_construction = 200,
+                nsq = 2,
+                sample_rate = 0.5,
+                auto_build
+            ))]
+            vector: Vec<f32>,
+        }
+
+        assert_eq!(
+            Segment::projections(),
+            vec!["segmentId", "bookName", "text", "vector"]
+        );
+        let schema = serde_json::to_value(Segment::table_schema())?;
+        let expect = serde_json::json!({
+            "fields": [
+                {"fieldName": "segmentId", "fieldType": "UINT64", "primaryKey": true, "partitionKey": true, "autoIncrement": true, "notNull": true},
+                {"fieldName": "bookName", "fieldType": "STRING", "primaryKey": false, "partitionKey": false, "autoIncrement": false, "notNull": true},
+                {"fieldName": "text", "fieldType": "TEXT", "primaryKey": false, "partitionKey": false, "autoIncrement": false, "notNull": false},
+                {"fieldName": "vector", "fieldType": "FLOAT_VECTOR", "primaryKey": false, "partitionKey": false, "autoIncrement": false, "notNull": true, "dimension": 4},
+            ],
+            "indexes": [
+                {"indexName": "bookName_idx", "indexType": "SECONDARY", "field": "bookName", "autoBuild": false},
+                {
+                    "indexName": "vector_idx",
+                    "indexType": "HNSWPQ",
+                    "metricType": "COSINE",
+                    "params": {"M": 16, "efConstruction": 200, "NSQ": 2, "sampleRate": 0.5},
+                    "field": "vector",
+                    "autoBuild": true,
+                },
+            ],
+        });
+        assert_eq!(schema, expect);
+        Ok(())
+    }
+
     #[test]
     fn descript_table_response_deserialize_test() -> Result<()> {
         let data = r#"
@@ -503,13 +754,16 @@ mod tests {
             }
         "#;
 
-        let v: DescriptTable = serde_json::from_str(&data)?;
+        let v: DescriptTable = serde_json::from_str(data)?;
         println!("{:?}", v);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_create_table() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        client.create_database(&TESTDATABSE).await?;
         let fields = vec![
             FieldSchemaBuilder::default()
                 .field_name("id")
@@ -559,23 +813,21 @@ mod tests {
             .database(TESTDATABSE.to_string())
             .table(TESTTABLE.to_string())
             .description("basic test".to_string())
-            .replication(3 as u32)
+            .replication(3_u32)
             .partition(Partition {
                 partition_type: PartitionType::HASH,
                 partition_num: 3,
             })
-            .schema(TableSchema {
-                fields: fields,
-                indexes: indexes,
-            })
+            .schema(TableSchema { fields, indexes })
             .build()?;
-        let create_table_resp = UTCLIENT.create_table(&args).await?;
+        let create_table_resp = client.create_table(&args).await?;
         println!("{:?}", create_table_resp);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_add_field() -> Result<()> {
+        let (_server, client) = test_client().await?;
         let fields = vec![FieldSchemaBuilder::default()
             .field_name("bookAlias")
             .field_type(FieldType::STRING)
@@ -584,39 +836,43 @@ mod tests {
             .database(TESTDATABSE.to_string())
             .table(TESTTABLE.to_string())
             .schema(TableSchema {
-                fields: fields,
+                fields,
                 indexes: vec![],
             })
             .build()?;
-        let ret = UTCLIENT.add_field(&args).await?;
+        let ret = client.add_field(&args).await?;
         println!("{:?}", ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_desc_table() -> Result<()> {
-        let ret = UTCLIENT.desc_table(&TESTDATABSE, &TESTTABLE).await?;
+        let (_server, client) = test_client().await?;
+        let ret = client.desc_table(&TESTDATABSE, &TESTTABLE).await?;
         println!("{:?}", ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_list_table() -> Result<()> {
-        let ret = UTCLIENT.list_table(&TESTDATABSE).await?;
+        let (_server, client) = test_client().await?;
+        let ret = client.list_table(&TESTDATABSE).await?;
         println!("{:?}", ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_stats_table() -> Result<()> {
-        let ret = UTCLIENT.show_table_stats(&TESTDATABSE, &TESTTABLE).await?;
+        let (_server, client) = test_client().await?;
+        let ret = client.show_table_stats(&TESTDATABSE, &TESTTABLE).await?;
         println!("{:?}", ret);
         Ok(())
     }
 
     #[tokio::test]
     async fn test_alias_table() -> Result<()> {
-        let ret = UTCLIENT
+        let (_server, client) = test_client().await?;
+        let ret = client
             .alias_table("book", "book_segments", "table_alias1")
             .await?;
         println!("{:?}", ret);
@@ -625,7 +881,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_unalias_table() -> Result<()> {
-        let ret = UTCLIENT
+        let (_server, client) = test_client().await?;
+        client
+            .alias_table("book", "book_segments", "table_alias1")
+            .await?;
+        let ret = client
             .unalias_table("book", "book_segments", "table_alias1")
             .await?;
         println!("{:?}", ret);
@@ -634,7 +894,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_drop_table() -> Result<()> {
-        let ret = UTCLIENT.drop_table(&TESTDATABSE, &TESTTABLE).await?;
+        let (_server, client) = test_client().await?;
+        let ret = client.drop_table(&TESTDATABSE, &TESTTABLE).await?;
         println!("{:?}", ret);
         Ok(())
     }
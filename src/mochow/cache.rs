@@ -0,0 +1,187 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+table schemas cached by the client
+ */
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::error::SdkError;
+
+use super::api::{DescriptTable, ServerErrorCode};
+
+/// described tables by database and table name, shared between clones of the client
+#[derive(Debug, Default)]
+pub(crate) struct SchemaCache {
+    tables: Mutex<HashMap<(String, String), (Instant, DescriptTable)>>,
+}
+
+impl SchemaCache {
+    /// the cached table if it's not older than the ttl
+    pub(crate) fn get(&self, data_base: &str, table: &str, ttl: Duration) -> Option<DescriptTable> {
+        let tables = self.tables.lock().unwrap();
+        tables
+            .get(&(data_base.to_string(), table.to_string()))
+            .filter(|(cached_at, _)| cached_at.elapsed() < ttl)
+            .map(|(_, table)| table.clone())
+    }
+
+    pub(crate) fn put(&self, table: DescriptTable) {
+        let mut tables = self.tables.lock().unwrap();
+        tables.insert(
+            (table.database.clone(), table.table.clone()),
+            (Instant::now(), table),
+        );
+    }
+
+    /// remove the table, or all tables of the database if the table is none
+    pub(crate) fn invalidate(&self, data_base: &str, table: Option<&str>) {
+        let mut tables = self.tables.lock().unwrap();
+        tables.retain(|(db, t), _| db != data_base || table.is_some_and(|table| t != table));
+    }
+
+    /// remove the table if the error implies its schema has changed
+    pub(crate) fn invalidate_on_error<T>(
+        &self,
+        data_base: &str,
+        table: &str,
+        result: Result<T, SdkError>,
+    ) -> Result<T, SdkError> {
+        if let Err(SdkError::ServiceError(e)) = &result {
+            if matches!(
+                e.server_code,
+                ServerErrorCode::INVALID_TABLE_SCHEMA
+                    | ServerErrorCode::DYNAMIC_SCHEMA_ERROR
+                    | ServerErrorCode::TABLE_NOT_EXIST
+                    | ServerErrorCode::FIELD_NOT_EXIST
+                    | ServerErrorCode::VECTOR_FIELD_NOT_EXIST
+                    | ServerErrorCode::INVALID_INDEX_SCHEMA
+                    | ServerErrorCode::INDEX_NOT_EXIST
+            ) {
+                self.invalidate(data_base, Some(table));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        error::SdkError,
+        mochow::{
+            api::*,
+            testing::{create_table_args, field, FakeMochowServer},
+        },
+    };
+
+    #[tokio::test]
+    async fn test_schema_cache() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        let other = server.client()?;
+        client.create_database("book").await?;
+        let args = create_table_args("segments", vec![field("id", FieldType::STRING)], vec![]);
+        client.create_table(&args).await?;
+        let fields = |table: DescriptTable| table.schema.fields.len();
+        assert_eq!(
+            fields(client.cached_desc_table("book", "segments").await?),
+            1
+        );
+
+        let add_field = |name: &str| {
+            AddFieldArgsBuilder::default()
+                .database("book")
+                .table("segments")
+                .schema(TableSchema {
+                    fields: vec![field(name, FieldType::UINT32)],
+                    indexes: vec![],
+                })
+                .build()
+                .unwrap()
+        };
+        // changes of other clients are not visible until invalidated
+        other.add_field(&add_field("page")).await?;
+        assert_eq!(
+            fields(client.cached_desc_table("book", "segments").await?),
+            1
+        );
+        client.invalidate_schema("book", "segments");
+        assert_eq!(
+            fields(client.cached_desc_table("book", "segments").await?),
+            2
+        );
+
+        // ddl of the client itself invalidates the cache
+        client.add_field(&add_field("chapter")).await?;
+        assert_eq!(
+            fields(client.cached_desc_table("book", "segments").await?),
+            3
+        );
+
+        // indexes on fields added by other clients are validated against a fresh schema
+        other.add_field(&add_field("volume")).await?;
+        let args = CreateIndexArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .indexes(vec![IndexSchemaBuilder::default()
+                .index_name("volume_idx")
+                .field("volume")
+                .index_type(IndexType::SECONDARY_INDEX)
+                .build()?])
+            .build()?;
+        client.create_index(&args).await?;
+        assert_eq!(
+            fields(client.cached_desc_table("book", "segments").await?),
+            4
+        );
+
+        // so do aliases of the table
+        client.alias_table("book", "segments", "latest").await?;
+        assert_eq!(
+            client.cached_desc_table("book", "segments").await?.aliases,
+            vec!["latest"]
+        );
+        client.unalias_table("book", "segments", "latest").await?;
+        assert!(client
+            .cached_desc_table("book", "segments")
+            .await?
+            .aliases
+            .is_empty());
+
+        // so do schema related errors
+        other.drop_table("book", "segments").await?;
+        assert_eq!(
+            fields(client.cached_desc_table("book", "segments").await?),
+            4
+        );
+        let args = QueryRowArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .primary_key(serde_json::json!({"id": "1"}))
+            .build()?;
+        assert!(client.query_row::<serde_json::Value>(&args).await.is_err());
+        assert!(matches!(
+            client.cached_desc_table("book", "segments").await,
+            Err(SdkError::ServiceError(_))
+        ));
+        Ok(())
+    }
+}
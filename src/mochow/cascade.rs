@@ -0,0 +1,268 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+drop a database with all of its tables
+ */
+use std::time::{Duration, Instant};
+
+use crate::error::SdkError;
+
+use super::{client::MochowClient, wait::is_not_exist};
+
+/// progress of [MochowClient::drop_database_cascade_with_progress]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CascadeDropEvent {
+    /// the alias of the table is removed
+    AliasRemoved {
+        table: String,
+        alias: String,
+    },
+    /// drop of the table is accepted, it may be DELETING for a while
+    TableDropping {
+        table: String,
+    },
+    /// the table is gone
+    TableDropped {
+        table: String,
+    },
+    /// the table can't be dropped, the database is kept
+    TableFailed {
+        table: String,
+        error: String,
+    },
+    DatabaseDropped,
+}
+
+/// result of [MochowClient::drop_database_cascade]
+#[derive(Debug, Default)]
+pub struct CascadeDropReport {
+    pub dropped_tables: Vec<String>,
+    /// tables failed to drop with the errors, the database is not dropped if it's not empty
+    pub failed_tables: Vec<(String, SdkError)>,
+    pub database_dropped: bool,
+}
+
+impl CascadeDropReport {
+    fn fail(
+        &mut self,
+        table: String,
+        error: SdkError,
+        progress: &mut impl FnMut(&CascadeDropEvent),
+    ) {
+        progress(&CascadeDropEvent::TableFailed {
+            table: table.clone(),
+            error: error.to_string(),
+        });
+        self.failed_tables.push((table, error));
+    }
+}
+
+impl MochowClient {
+    /// drop all tables of the database with their aliases, wait until they are gone,
+    /// then drop the database, tables failed to drop are reported instead of returned as error,
+    /// the confirmation of a protected database also confirms dropping its tables,
+    /// protected tables of other databases are reported as failed without their own confirmation,
+    /// see [MochowClient::drop_database_cascade_with_progress]
+    pub async fn drop_database_cascade(
+        &self,
+        data_base: &str,
+        timeout: Duration,
+    ) -> Result<CascadeDropReport, SdkError> {
+        self.drop_database_cascade_with_progress(data_base, timeout, |_| {})
+            .await
+    }
+
+    /// same as [MochowClient::drop_database_cascade], with the progress reported to the callback,
+    /// the timeout is for waiting the dropped tables to disappear
+    /// ```rust,ignore
+    /// let report = client
+    ///     .drop_database_cascade_with_progress("book", Duration::from_secs(60), |event| {
+    ///         println!("{:?}", event);
+    ///     })
+    ///     .await?;
+    /// assert!(report.database_dropped);
+    /// ```
+    pub async fn drop_database_cascade_with_progress(
+        &self,
+        data_base: &str,
+        timeout: Duration,
+        mut progress: impl FnMut(&CascadeDropEvent),
+    ) -> Result<CascadeDropReport, SdkError> {
+        self.check_protected(data_base, None)?;
+        let deadline = Instant::now() + timeout;
+        let mut report = CascadeDropReport::default();
+        let mut dropping = vec![];
+        for table in self.list_table(data_base).await?.tables {
+            match self.drop_table_and_aliases(data_base, &table).await {
+                Ok(aliases) => {
+                    for alias in aliases {
+                        progress(&CascadeDropEvent::AliasRemoved {
+                            table: table.clone(),
+                            alias,
+                        });
+                    }
+                    progress(&CascadeDropEvent::TableDropping {
+                        table: table.clone(),
+                    });
+                    dropping.push(table);
+                }
+                Err(e) => report.fail(table, e, &mut progress),
+            }
+        }
+        for table in dropping {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.wait_table_dropped(data_base, &table, remaining).await {
+                Ok(()) => {
+                    progress(&CascadeDropEvent::TableDropped {
+                        table: table.clone(),
+                    });
+                    report.dropped_tables.push(table);
+                }
+                Err(e) => report.fail(table, e, &mut progress),
+            }
+        }
+        if report.failed_tables.is_empty() {
+            self.drop_database(data_base).await?;
+            report.database_dropped = true;
+            progress(&CascadeDropEvent::DatabaseDropped);
+        }
+        Ok(report)
+    }
+
+    // remove the aliases of the table and drop it, return the removed aliases,
+    // a protected table needs its own confirmation unless its protected database is confirmed
+    async fn drop_table_and_aliases(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<Vec<String>, SdkError> {
+        let client = if self.database_confirmed(data_base) {
+            self.with_confirmation(&format!("{}.{}", data_base, table))
+        } else {
+            self.clone()
+        };
+        client.check_protected(data_base, Some(table))?;
+        let aliases = match self.desc_table(data_base, table).await {
+            Ok(resp) => resp.table.aliases,
+            Err(e) if is_not_exist(&e) => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+        for alias in &aliases {
+            self.unalias_table(data_base, table, alias).await?;
+        }
+        match client.drop_table(data_base, table).await {
+            Err(e) if !is_not_exist(&e) => Err(e),
+            _ => Ok(aliases),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::mochow::{
+        api::*,
+        config::ClientConfigurationBuilder,
+        testing::{self, field, FakeMochowServer},
+    };
+
+    fn create_table_args(table: &str) -> CreateTableArgs {
+        testing::create_table_args(table, vec![field("id", FieldType::STRING)], vec![])
+    }
+
+    #[tokio::test]
+    async fn test_drop_database_cascade() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        client.create_database("book").await?;
+        client.create_table(&create_table_args("segments")).await?;
+        client.create_table(&create_table_args("chapters")).await?;
+        client.alias_table("book", "segments", "latest").await?;
+
+        // tables of protected databases can't be dropped without confirmation
+        let config = ClientConfigurationBuilder::default()
+            .account("root")
+            .api_key("fake_api_key")
+            .endpoint(server.endpoint())
+            .protected_databases(vec!["book".to_string()])
+            .build()?;
+        let protected = MochowClient::new_with_configuration(&config)?;
+        assert!(matches!(
+            protected
+                .drop_database_cascade("book", Duration::from_secs(5))
+                .await,
+            Err(SdkError::ConfirmationRequired(_))
+        ));
+
+        let report = protected
+            .with_confirmation("book")
+            .drop_database_cascade("book", Duration::from_secs(5))
+            .await?;
+        assert!(report.database_dropped);
+        assert!(report.failed_tables.is_empty());
+        assert_eq!(report.dropped_tables, vec!["chapters", "segments"]);
+        assert!(client.list_database().await?.databases.is_empty());
+
+        client.create_database("book").await?;
+        client.create_table(&create_table_args("segments")).await?;
+        client.create_table(&create_table_args("chapters")).await?;
+        client.alias_table("book", "segments", "latest").await?;
+
+        // a protected table of a database not protected is kept without its own confirmation
+        let config = ClientConfigurationBuilder::default()
+            .account("root")
+            .api_key("fake_api_key")
+            .endpoint(server.endpoint())
+            .protected_databases(vec!["other".to_string()])
+            .protected_tables(vec!["book.segments".to_string()])
+            .build()?;
+        let protected = MochowClient::new_with_configuration(&config)?;
+        let report = protected
+            .drop_database_cascade("book", Duration::from_secs(5))
+            .await?;
+        assert!(!report.database_dropped);
+        assert_eq!(report.dropped_tables, vec!["chapters"]);
+        assert!(matches!(
+            report.failed_tables.as_slice(),
+            [(table, SdkError::ConfirmationRequired(_))] if table == "segments"
+        ));
+        let segments = client.desc_table("book", "segments").await?.table;
+        assert_eq!(segments.aliases, vec!["latest"]);
+        client.create_table(&create_table_args("chapters")).await?;
+
+        let mut events = vec![];
+        let report = client
+            .drop_database_cascade_with_progress("book", Duration::from_secs(5), |event| {
+                events.push(event.clone())
+            })
+            .await?;
+        assert!(report.database_dropped);
+        assert!(report.failed_tables.is_empty());
+        assert_eq!(report.dropped_tables, vec!["chapters", "segments"]);
+        assert_eq!(events.len(), 6);
+        assert_eq!(
+            events[1],
+            CascadeDropEvent::AliasRemoved {
+                table: "segments".to_string(),
+                alias: "latest".to_string(),
+            }
+        );
+        assert_eq!(events[5], CascadeDropEvent::DatabaseDropped);
+        assert!(client.list_database().await?.databases.is_empty());
+        Ok(())
+    }
+}
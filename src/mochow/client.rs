@@ -15,18 +15,22 @@
 /*
 client to connect to mochow server
  */
-use std::time::Duration;
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use derive_builder::Builder;
 use reqwest::Response;
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, RequestBuilder};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, Middleware, RequestBuilder};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use reqwest_tracing::TracingMiddleware;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{auth::credentials, error::SdkError};
 
-use super::{api::*, config::*};
+use super::{api::*, cache::SchemaCache, config::*};
 
 #[derive(Debug, Clone, Builder)]
 pub struct MochowClient {
@@ -39,15 +43,110 @@ pub struct MochowClient {
 
     #[builder(setter(into))]
     pub configuration: ClientConfiguration,
+
+    /// requests recorded in dry run mode, shared between clones of the client
+    #[builder(default, setter(skip))]
+    pub(crate) dry_run_plan: Arc<Mutex<Vec<DryRunRequest>>>,
+
+    /// schemas described for validation, shared between clones of the client
+    #[builder(default, setter(skip))]
+    pub(crate) schema_cache: Arc<SchemaCache>,
+
+    /// confirmation token for destructive operations on protected objects,
+    /// see [MochowClient::with_confirmation]
+    #[builder(default, setter(skip))]
+    pub(crate) confirmation: Option<String>,
 }
 
 /// every request should imple IntoRequest trait, it's just a rest request for http client
+///
+/// it's also the extension point for apis which are not wrapped by the sdk:
+/// implement IntoRequest for your own args and send them by [MochowClient::send],
+/// the client adds authentication, headers and timeout, retries transient errors
+/// and maps error responses to [crate::error::SdkError::ServiceError] as it does for built-in calls.
+/// ```rust,ignore
+/// use mochow_sdk_rust::mochow::{client::IntoRequest, config::ClientConfiguration};
+/// use mochow_sdk_rust::reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct ListUserArgs {}
+///
+/// impl IntoRequest for ListUserArgs {
+///     fn into_request(self, config: &ClientConfiguration, client: &ClientWithMiddleware) -> RequestBuilder {
+///         // url is made up of endpoint, api version and action
+///         let url = format!("{}/{}/user?list", config.endpoint, config.version);
+///         client.post(url).json(&self)
+///     }
+///
+///     // only read data, so the request is still sent in dry run mode and by read only client
+///     fn is_read_only(&self) -> bool {
+///         true
+///     }
+/// }
+///
+/// let ret: serde_json::Value = client.send(ListUserArgs {}).await?;
+/// ```
 pub trait IntoRequest {
+    /// build the http request with the endpoint and version in config,
+    /// authentication, headers and timeout are added by the client
     fn into_request(
         self,
         config: &ClientConfiguration,
         client: &ClientWithMiddleware,
     ) -> RequestBuilder;
+
+    /// whether the request only reads data, default is false,
+    /// requests which are not read only are recorded instead of sent in dry run mode,
+    /// and rejected by a read only client
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
+    /// the database and optionally the table the request destroys, default is none,
+    /// the client requires a confirmation if it's protected, see [MochowClient::with_confirmation]
+    fn protected_target(&self) -> Option<(String, Option<String>)> {
+        None
+    }
+}
+
+/// a request recorded by the client in dry run mode, see [MochowClient::dry_run_plan]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRunRequest {
+    /// http method, like POST
+    pub method: String,
+    /// path and query of the request, like /v1/table?create
+    pub path: String,
+    /// json body of the request, Null if the request has no body
+    pub body: serde_json::Value,
+}
+
+impl DryRunRequest {
+    pub(crate) fn from_request(req: &reqwest::Request) -> Self {
+        let path = match req.url().query() {
+            Some(query) => format!("{}?{}", req.url().path(), query),
+            None => req.url().path().to_string(),
+        };
+        let body = match req.body().and_then(|body| body.as_bytes()) {
+            Some(bytes) if !bytes.is_empty() => {
+                serde_json::from_slice(bytes).unwrap_or_else(|_| {
+                    serde_json::Value::String(String::from_utf8_lossy(bytes).into())
+                })
+            }
+            _ => serde_json::Value::Null,
+        };
+        Self {
+            method: req.method().to_string(),
+            path,
+            body,
+        }
+    }
+}
+
+impl Display for DryRunRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.method, self.path, self.body)
+    }
 }
 
 impl MochowClient {
@@ -66,28 +165,44 @@ impl MochowClient {
         )
     }
 
-    // get the http client with config
-    fn _http_client(config: &ClientConfiguration) -> ClientWithMiddleware {
+    // get the http client with config, extra middlewares run after retry, once per attempt
+    fn _http_client(
+        config: &ClientConfiguration,
+        middlewares: &[Arc<dyn Middleware>],
+    ) -> ClientWithMiddleware {
         let retry_policy = ExponentialBackoff::builder().build_with_max_retries(config.max_retries);
-        ClientBuilder::new(reqwest::Client::new())
+        let mut builder = ClientBuilder::new(reqwest::Client::new())
             // Trace HTTP request
             .with(TracingMiddleware::default())
             // Retry
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .build()
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy));
+        for middleware in middlewares {
+            builder = builder.with_arc(middleware.clone());
+        }
+        builder.build()
     }
 
     /// create a new mochow client with configuration
     /// ```rust
     /// use mochow_rust_sdk::mochow::{config::ClientConfigurationBuilder, client::MochowClient};
     /// let config = ClientConfigurationBuilder::default()
-    /// 	.account("account")
-    /// 	.api_key("api_key")
-    /// 	.endpoint("endpoint")
-    /// 	.build().unwrap();
+    ///     .account("account")
+    ///     .api_key("api_key")
+    ///     .endpoint("endpoint")
+    ///     .build().unwrap();
     /// let client = MochowClient::new_with_configuration(&config).unwrap();
     /// ```
     pub fn new_with_configuration(config: &ClientConfiguration) -> Result<Self, SdkError> {
+        Self::new_with_middleware(config, vec![])
+    }
+
+    /// create a new mochow client with configuration and extra middlewares of the http client,
+    /// like [crate::mochow::middleware::FixtureMiddleware],
+    /// the middlewares run in order after the retry middleware, so they see every attempt
+    pub fn new_with_middleware(
+        config: &ClientConfiguration,
+        middlewares: Vec<Arc<dyn Middleware>>,
+    ) -> Result<Self, SdkError> {
         let mut config = config.clone();
         if config.account.is_empty() || config.api_key.is_empty() || config.endpoint.is_empty() {
             return Err(SdkError::ParamsError(
@@ -104,12 +219,28 @@ impl MochowClient {
         config.endpoint = endpoint;
         let ret = MochowClientBuilder::default()
             .credential(auth)
-            .http_client(Self::_http_client(&config))
+            .http_client(Self::_http_client(&config, &middlewares))
             .configuration(config)
             .build()?;
         Ok(ret)
     }
 
+    /// return a client carrying a confirmation token, which is required by drop_database, drop_table,
+    /// delete_index and delete_rows by filter on protected databases or tables, however they are sent,
+    /// and by mutating [RawRequest]s on them, see [IntoRequest::protected_target],
+    /// the token is the name of the target, "database" for drop_database and "database.table" for others
+    /// ```rust,ignore
+    /// let _ = client
+    ///     .with_confirmation("book.book_segments")
+    ///     .drop_table("book", "book_segments")
+    ///     .await?;
+    /// ```
+    pub fn with_confirmation(&self, token: &str) -> Self {
+        let mut client = self.clone();
+        client.confirmation = Some(token.to_string());
+        client
+    }
+
     /// create a database
     /// ```rust
     /// let _ = client.create_database("test").await?;
@@ -118,9 +249,7 @@ impl MochowClient {
         let args = CreateDatabaseArgsBuilder::default()
             .database(data_base)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        self.send_request(args).await
     }
 
     /// drop the database you created, before deleting the database, all tables in the database must be deleted in advance
@@ -131,9 +260,9 @@ impl MochowClient {
         let args = DropDatabaseArgsBuilder::default()
             .database(data_base)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let ret = self.send_request(args).await;
+        self.schema_cache.invalidate(data_base, None);
+        ret
     }
 
     /// list current all databases
@@ -143,9 +272,7 @@ impl MochowClient {
     /// ```
     pub async fn list_database(&self) -> Result<ListDatabaseResponse, SdkError> {
         let args = ListDatabaseArgsBuilder::default().build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<ListDatabaseResponse>().await?)
+        self.send_request(args).await
     }
 
     /// check if the database is exist,
@@ -180,9 +307,11 @@ impl MochowClient {
     /// println!("{:?}", create_table_resp);
     /// ```
     pub async fn create_table(&self, args: &CreateTableArgs) -> Result<CommonResponse, SdkError> {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        args.validate()?;
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate(&args.database, Some(&args.table));
+        ret
     }
 
     /// drop table
@@ -195,9 +324,9 @@ impl MochowClient {
             .database(data_base)
             .table(table)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let ret = self.send_request(args).await;
+        self.schema_cache.invalidate(data_base, Some(table));
+        ret
     }
 
     /// list table
@@ -205,9 +334,7 @@ impl MochowClient {
         let args = ListTableArgsBuilder::default()
             .database(data_base)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<ListTableResponse>().await?)
+        self.send_request(args).await
     }
 
     /// has table
@@ -226,9 +353,31 @@ impl MochowClient {
             .database(data_base)
             .table(table)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<DescriptTableResponse>().await?)
+        let ret: Result<DescriptTableResponse, SdkError> = self.send_request(args).await;
+        if let Ok(resp) = &ret {
+            self.schema_cache.put(resp.table.clone());
+        }
+        ret
+    }
+
+    /// descript table from the schema cache, the table is described if it's not cached
+    /// or older than [ClientConfiguration::schema_cache_ttl_seconds],
+    /// the cache is invalidated by ddl of the client and schema related errors of the server
+    pub async fn cached_desc_table(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<DescriptTable, SdkError> {
+        let ttl = Duration::from_secs(self.configuration.schema_cache_ttl_seconds);
+        if let Some(cached) = self.schema_cache.get(data_base, table, ttl) {
+            return Ok(cached);
+        }
+        Ok(self.desc_table(data_base, table).await?.table)
+    }
+
+    /// remove the table from the schema cache, for schema changes made by other clients
+    pub fn invalidate_schema(&self, data_base: &str, table: &str) {
+        self.schema_cache.invalidate(data_base, Some(table));
     }
 
     /// add field for table, currently only supports adding scalar fields
@@ -249,9 +398,10 @@ impl MochowClient {
     /// let ret = client.add_field(&args).await?;
     /// ```
     pub async fn add_field(&self, args: &AddFieldArgs) -> Result<CommonResponse, SdkError> {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate(&args.database, Some(&args.table));
+        ret
     }
 
     /// show table stats
@@ -264,9 +414,7 @@ impl MochowClient {
             .database(data_base)
             .table(table)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<StatsTableResponse>().await?)
+        self.send_request(args).await
     }
 
     /// alias table
@@ -281,9 +429,9 @@ impl MochowClient {
             .table(table)
             .alias(alias)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let ret = self.send_request(args).await?;
+        self.invalidate_schema(data_base, table);
+        Ok(ret)
     }
 
     /// unalias table
@@ -298,12 +446,14 @@ impl MochowClient {
             .table(table)
             .alias(alias)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let ret = self.send_request(args).await?;
+        self.invalidate_schema(data_base, table);
+        Ok(ret)
     }
 
-    /// create index, only support for vector index
+    /// create index, only support for vector index,
+    /// the indexes are validated against the cached schema of the table before sending,
+    /// and against a freshly described one before they are rejected
     /// ```rust
     /// let indexes = vec![IndexSchemaBuilder::default()
     ///     .index_name("vector_idx")
@@ -323,9 +473,27 @@ impl MochowClient {
     /// let ret = client.create_index(&args).await?;
     /// ```
     pub async fn create_index(&self, args: &CreateIndexArgs) -> Result<CommonResponse, SdkError> {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let schema = match self.cached_desc_table(&args.database, &args.table).await {
+            Ok(table) => Some(table.schema),
+            // the schema is not available in dry run mode without read through
+            Err(SdkError::DryRunError(_)) => None,
+            Err(e) => return Err(e),
+        };
+        let mut validated = args.validate(schema.as_ref());
+        if validated.is_err() && schema.is_some() {
+            // the cached schema may miss fields added since, check against a fresh one before rejecting
+            self.invalidate_schema(&args.database, &args.table);
+            let schema = self
+                .cached_desc_table(&args.database, &args.table)
+                .await?
+                .schema;
+            validated = args.validate(Some(&schema));
+        }
+        validated?;
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate(&args.database, Some(&args.table));
+        ret
     }
 
     /// descript index
@@ -340,9 +508,7 @@ impl MochowClient {
             .table(table)
             .index_name(index_name)
             .build()?;
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<DescriptIndexResponse>().await?)
+        self.send_request(args).await
     }
 
     /// modify vector index info, only support 'autobuild' attribute
@@ -365,9 +531,10 @@ impl MochowClient {
     /// let ret = client.modify_index(&args).await?;
     /// ```
     pub async fn modify_index(&self, args: &ModifyIndexArgs) -> Result<CommonResponse, SdkError> {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate(&args.database, Some(&args.table));
+        ret
     }
 
     /// rebuild index, only support for vector index
@@ -382,9 +549,7 @@ impl MochowClient {
             .table(table)
             .index_name(index_name)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        self.send_request(args).await
     }
 
     /// delete index
@@ -399,9 +564,9 @@ impl MochowClient {
             .table(table)
             .index_name(index_name)
             .build()?;
-        let req = self.prepare_request(args);
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let ret = self.send_request(args).await;
+        self.schema_cache.invalidate(data_base, Some(table));
+        ret
     }
 
     /// insert row, when the primary key of the record already exists, an insertion error occurs, not support insert batch atomicity
@@ -450,9 +615,12 @@ impl MochowClient {
         &self,
         args: &InsertRowArgs<T>,
     ) -> Result<InsertRowsResponse, SdkError> {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<InsertRowsResponse>().await?)
+        if let Some(table) = self.row_schema(&args.database, &args.table).await? {
+            args.validate(&table)?;
+        }
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate_on_error(&args.database, &args.table, ret)
     }
 
     /// upsert row, when the primary key of the record already exists, overwrite the old data with the new data as a whole, not support insert batch atomicity
@@ -476,9 +644,12 @@ impl MochowClient {
         &self,
         args: &UpsertRowArgs<T>,
     ) -> Result<UpsertRowsResponse, SdkError> {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<UpsertRowsResponse>().await?)
+        if let Some(table) = self.row_schema(&args.database, &args.table).await? {
+            args.validate(&table)?;
+        }
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate_on_error(&args.database, &args.table, ret)
     }
 
     /// update row, update the value of one or more scalar fields in a specified record
@@ -498,9 +669,9 @@ impl MochowClient {
     /// let _ret = client.update_row(&args).await?;
     /// ```
     pub async fn update_row(&self, args: &UpdateRowArgs) -> Result<CommonResponse, SdkError> {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate_on_error(&args.database, &args.table, ret)
     }
 
     /// delete rows, you can delete multiple records by primary key, or filter the records to be deleted
@@ -513,12 +684,12 @@ impl MochowClient {
     ///     }))
     ///     .filter("page >= 22")
     ///     .build()?;
-    /// let _ret = UTCLIENT.delete_rows(&args).await?;
+    /// let _ret = client.delete_rows(&args).await?;
     /// ```
     pub async fn delete_rows(&self, args: &DeleteRowArgs) -> Result<CommonResponse, SdkError> {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<CommonResponse>().await?)
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate_on_error(&args.database, &args.table, ret)
     }
 
     /// query row, query single row by primary key
@@ -532,10 +703,10 @@ impl MochowClient {
     ///     .projections(vec!["id".to_string(), "bookName".to_string()])
     ///     .retrieve_vector(false)
     ///     .build()?;
-    /// let query_ret: QueryRowsResponse<MyRecord> = UTCLIENT.query_row(&args).await?;
+    /// let query_ret: QueryRowsResponse<MyRecord> = client.query_row(&args).await?;
     /// println!("query_row ret: {:?}", query_ret.row);
     /// let row1 = query_ret.row;
-    /// let query_ret: QueryRowsResponse<serde_json::Value> = UTCLIENT.query_row(&args).await?;
+    /// let query_ret: QueryRowsResponse<serde_json::Value> = client.query_row(&args).await?;
     /// println!("query_row ret: {:?}", query_ret.row);
     /// // convert json value to struct
     /// let row2 = serde_json::from_value(query_ret.row)?;
@@ -545,9 +716,9 @@ impl MochowClient {
     where
         T: for<'de> Deserialize<'de>,
     {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<QueryRowsResponse<T>>().await?)
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate_on_error(&args.database, &args.table, ret)
     }
 
     /// search rows
@@ -581,9 +752,9 @@ impl MochowClient {
     where
         T: for<'de> Deserialize<'de>,
     {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<SearchRowsResponse<T>>().await?)
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate_on_error(&args.database, &args.table, ret)
     }
 
     /// select rows
@@ -617,9 +788,9 @@ impl MochowClient {
     where
         T: for<'de> Deserialize<'de>,
     {
-        let req = self.prepare_request(args.clone());
-        let res = req.send_and_log().await?;
-        Ok(res.json::<SelectRowsResponse<T>>().await?)
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate_on_error(&args.database, &args.table, ret)
     }
 
     /// batch search rows
@@ -645,7 +816,7 @@ impl MochowClient {
     ///     .retrieve_vector(true)
     ///     .build()?;
     /// let batch_rets: BatchSearchRowsResponse<serde_json::Value> =
-    ///     UTCLIENT.batch_search_rows(&batch_search_args).await?;
+    ///     client.batch_search_rows(&batch_search_args).await?;
     /// for (i, bs) in batch_rets.results.iter().enumerate() {
     ///     println!("batch: {}, {:?}", i, bs.search_vector_floats);
     ///     for (j, ss) in bs.rows.iter().enumerate() {
@@ -660,9 +831,169 @@ impl MochowClient {
     where
         T: for<'de> Deserialize<'de>,
     {
-        let req = self.prepare_request(args.clone());
+        let ret = self.send_request(args.clone()).await;
+        self.schema_cache
+            .invalidate_on_error(&args.database, &args.table, ret)
+    }
+
+    /// send any request implementing [IntoRequest] and decode the response as `T`,
+    /// with the same authentication, retries, error mapping, dry run and read only handling as built-in calls,
+    /// and the same confirmation of destructive operations on protected databases or tables
+    pub async fn send<R, T>(&self, args: R) -> Result<T, SdkError>
+    where
+        R: IntoRequest,
+        T: DeserializeOwned,
+    {
+        self.send_request(args).await
+    }
+
+    /// send a request to an endpoint which is not wrapped by the sdk yet,
+    /// the request reuses authentication, headers, timeout, retries and error mapping of the client,
+    /// `path_and_query` includes the api version, like "/v1/table?desc",
+    /// GET and HEAD requests are read only for dry run and read only mode, others are mutating,
+    /// send a [RawRequest] to mark a request read only explicitly,
+    /// mutating requests on protected databases or tables in the body require a confirmation
+    /// ```rust,ignore
+    /// let ret: serde_json::Value = client
+    ///     .execute(
+    ///         reqwest::Method::POST,
+    ///         "/v1/table?desc",
+    ///         Some(serde_json::json!({"database": "test_db", "table": "test_table"})),
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn execute<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path_and_query: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<T, SdkError> {
+        let args = RawRequest {
+            method,
+            path_and_query: path_and_query.to_string(),
+            body,
+            read_only: None,
+        };
+        self.send_request(args).await
+    }
+
+    /// requests recorded in dry run mode so far, in the order they were issued
+    /// ```rust,ignore
+    /// let config = ClientConfigurationBuilder::default()
+    ///     .account("account")
+    ///     .api_key("api_key")
+    ///     .endpoint("endpoint")
+    ///     .dry_run(true)
+    ///     .build()?;
+    /// let client = MochowClient::new_with_configuration(&config)?;
+    /// let _ = client.drop_table("test_db", "test_table").await?;
+    /// for req in client.dry_run_plan() {
+    ///     println!("{}", req);
+    /// }
+    /// ```
+    pub fn dry_run_plan(&self) -> Vec<DryRunRequest> {
+        self.dry_run_plan.lock().unwrap().clone()
+    }
+
+    /// return the requests recorded in dry run mode and clear the plan
+    pub fn take_dry_run_plan(&self) -> Vec<DryRunRequest> {
+        std::mem::take(&mut *self.dry_run_plan.lock().unwrap())
+    }
+
+    async fn send_request<T: DeserializeOwned>(
+        &self,
+        args: impl IntoRequest,
+    ) -> Result<T, SdkError> {
+        if let Some((data_base, table)) = args.protected_target() {
+            self.check_protected(&data_base, table.as_deref())?;
+        }
+        if self.configuration.read_only && !args.is_read_only() {
+            let req = self.prepare_request(args).build()?;
+            return Err(SdkError::ReadOnlyError(format!(
+                "client is read only, request rejected: {}",
+                DryRunRequest::from_request(&req)
+            )));
+        }
+        if self.configuration.dry_run {
+            if !args.is_read_only() {
+                return self.record_dry_run(args);
+            }
+            if !self.configuration.dry_run_read_through {
+                return Err(SdkError::DryRunError(
+                    "read only request is not sent when dry_run_read_through is disabled"
+                        .to_string(),
+                ));
+            }
+        }
+        let req = self.prepare_request(args);
         let res = req.send_and_log().await?;
-        Ok(res.json::<BatchSearchRowsResponse<T>>().await?)
+        Ok(res.json::<T>().await?)
+    }
+
+    // schema of the table for row validation, none if validate_rows is disabled
+    // or the schema is not available in dry run mode without read through
+    async fn row_schema(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<Option<DescriptTable>, SdkError> {
+        if !self.configuration.validate_rows {
+            return Ok(None);
+        }
+        match self.cached_desc_table(data_base, table).await {
+            Ok(table) => Ok(Some(table)),
+            Err(SdkError::DryRunError(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // record the request into dry run plan and return a success response without sending it
+    fn record_dry_run<T: DeserializeOwned>(&self, args: impl IntoRequest) -> Result<T, SdkError> {
+        let req = self.prepare_request(args).build()?;
+        self.dry_run_plan
+            .lock()
+            .unwrap()
+            .push(DryRunRequest::from_request(&req));
+        serde_json::from_value(serde_json::json!({
+            "code": 0,
+            "msg": "dry run",
+            "affectedCount": 0,
+        }))
+        .map_err(|e| SdkError::OtherError(e.into()))
+    }
+
+    // whether the database is protected and confirmed, which confirms dropping its tables too
+    pub(crate) fn database_confirmed(&self, data_base: &str) -> bool {
+        self.configuration
+            .protected_databases
+            .iter()
+            .any(|db| db == data_base)
+            && self.confirmation.as_deref() == Some(data_base)
+    }
+
+    // check the confirmation token for destructive operations on protected databases or tables
+    pub(crate) fn check_protected(
+        &self,
+        data_base: &str,
+        table: Option<&str>,
+    ) -> Result<(), SdkError> {
+        let target = match table {
+            Some(table) => format!("{}.{}", data_base, table),
+            None => data_base.to_string(),
+        };
+        let protected = self
+            .configuration
+            .protected_databases
+            .iter()
+            .any(|db| db == data_base)
+            || (table.is_some() && self.configuration.protected_tables.contains(&target));
+        if !protected || self.confirmation.as_deref() == Some(target.as_str()) {
+            return Ok(());
+        }
+        Err(SdkError::ConfirmationRequired(format!(
+            "{} is protected, use with_confirmation(\"{}\") to confirm the operation",
+            target, target
+        )))
     }
 
     fn prepare_request(&self, req: impl IntoRequest) -> RequestBuilder {
@@ -677,6 +1008,72 @@ impl MochowClient {
     }
 }
 
+/// a request to an endpoint which is not wrapped by the sdk yet, send it by [MochowClient::send],
+/// see [MochowClient::execute]
+/// ```rust,ignore
+/// // desc is a POST request which only reads data
+/// let args = RawRequestBuilder::default()
+///     .method(reqwest::Method::POST)
+///     .path_and_query("/v1/table?desc")
+///     .body(serde_json::json!({"database": "test_db", "table": "test_table"}))
+///     .read_only(true)
+///     .build()?;
+/// let ret: serde_json::Value = client.send(args).await?;
+/// ```
+#[derive(Debug, Clone, Builder)]
+pub struct RawRequest {
+    pub method: reqwest::Method,
+    /// path and query including the api version, like "/v1/table?desc"
+    #[builder(setter(into))]
+    pub path_and_query: String,
+    #[builder(default, setter(strip_option))]
+    pub body: Option<serde_json::Value>,
+    /// whether the request only reads data, by default GET and HEAD requests are read only,
+    /// see [IntoRequest::is_read_only]
+    #[builder(default, setter(strip_option))]
+    pub read_only: Option<bool>,
+}
+
+impl IntoRequest for RawRequest {
+    fn into_request(
+        self,
+        config: &ClientConfiguration,
+        client: &ClientWithMiddleware,
+    ) -> RequestBuilder {
+        let url = format!(
+            "{}/{}",
+            config.endpoint.trim_end_matches('/'),
+            self.path_and_query.trim_start_matches('/')
+        );
+        let req = client.request(self.method, url);
+        match self.body {
+            Some(body) => req.json(&body),
+            None => req,
+        }
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.read_only.unwrap_or(matches!(
+            self.method,
+            reqwest::Method::GET | reqwest::Method::HEAD
+        ))
+    }
+
+    // the database and table in the body of a mutating request, it may destroy them
+    fn protected_target(&self) -> Option<(String, Option<String>)> {
+        if self.is_read_only() {
+            return None;
+        }
+        let body = self.body.as_ref()?;
+        let data_base = body.get("database")?.as_str()?.to_string();
+        let table = body
+            .get("table")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string);
+        Some((data_base, table))
+    }
+}
+
 trait SendAndLog {
     async fn send_and_log(self) -> Result<Response, SdkError>;
 }
@@ -706,7 +1103,7 @@ impl SendAndLog for RequestBuilder {
             };
             return Err(SdkError::ServiceError(ServiceError {
                 status_code: status_code.as_u16() as i32,
-                request_id: request_id,
+                request_id,
                 server_code: msg.clone().code.into(),
                 resp: msg,
             }));
@@ -714,3 +1111,325 @@ impl SendAndLog for RequestBuilder {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    fn dry_run_client(read_through: bool) -> Result<MochowClient> {
+        let config = ClientConfigurationBuilder::default()
+            .account("account")
+            .api_key("api_key")
+            .endpoint("127.0.0.1:1")
+            .dry_run(true)
+            .dry_run_read_through(read_through)
+            .build()?;
+        Ok(MochowClient::new_with_configuration(&config)?)
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_records_mutations() -> Result<()> {
+        let client = dry_run_client(false)?;
+        let ret = client.drop_table("test_db", "test_table").await?;
+        assert_eq!(ret.code, 0);
+        let args = InsertRowArgsBuilder::default()
+            .database("test_db")
+            .table("test_table")
+            .rows(vec![serde_json::json!({"id": "0001"})])
+            .build()?;
+        let ret = client.insert_row(&args).await?;
+        assert_eq!(ret.affected_count, 0);
+
+        let plan = client.dry_run_plan();
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].method, "DELETE");
+        assert_eq!(plan[0].path, "/v1/table");
+        assert_eq!(
+            plan[0].body,
+            serde_json::json!({"database": "test_db", "table": "test_table"})
+        );
+        assert_eq!(plan[1].method, "POST");
+        assert_eq!(plan[1].path, "/v1/row?insert");
+        println!("{}", plan[1]);
+
+        assert_eq!(client.take_dry_run_plan().len(), 2);
+        assert!(client.dry_run_plan().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_without_read_through() -> Result<()> {
+        let client = dry_run_client(false)?;
+        let ret = client.list_table("test_db").await;
+        assert!(matches!(ret, Err(SdkError::DryRunError(_))));
+        assert!(client.dry_run_plan().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_only_rejects_mutations() -> Result<()> {
+        let config = ClientConfigurationBuilder::default()
+            .account("account")
+            .api_key("api_key")
+            .endpoint("127.0.0.1:1")
+            .read_only(true)
+            .dry_run(true)
+            .build()?;
+        let client = MochowClient::new_with_configuration(&config)?;
+        let ret = client.create_database("test_db").await;
+        assert!(matches!(ret, Err(SdkError::ReadOnlyError(_))));
+        assert!(client.dry_run_plan().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_protected_objects_require_confirmation() -> Result<()> {
+        let config = ClientConfigurationBuilder::default()
+            .account("account")
+            .api_key("api_key")
+            .endpoint("127.0.0.1:1")
+            .dry_run(true)
+            .protected_databases(vec!["prod_db".to_string()])
+            .protected_tables(vec!["test_db.prod_table".to_string()])
+            .build()?;
+        let client = MochowClient::new_with_configuration(&config)?;
+
+        let ret = client.drop_database("prod_db").await;
+        assert!(matches!(ret, Err(SdkError::ConfirmationRequired(_))));
+        let ret = client
+            .delete_index("prod_db", "any_table", "vector_idx")
+            .await;
+        assert!(matches!(ret, Err(SdkError::ConfirmationRequired(_))));
+        let ret = client.drop_table("test_db", "prod_table").await;
+        assert!(matches!(ret, Err(SdkError::ConfirmationRequired(_))));
+        let ret = client
+            .with_confirmation("test_db")
+            .drop_table("test_db", "prod_table")
+            .await;
+        assert!(matches!(ret, Err(SdkError::ConfirmationRequired(_))));
+
+        // delete by primary key is not guarded
+        let args = DeleteRowArgsBuilder::default()
+            .database("test_db")
+            .table("prod_table")
+            .primary_key(serde_json::json!({"id": "0001"}))
+            .build()?;
+        client.delete_rows(&args).await?;
+        let args = DeleteRowArgsBuilder::default()
+            .database("test_db")
+            .table("prod_table")
+            .filter("page >= 22")
+            .build()?;
+        let ret = client.delete_rows(&args).await;
+        assert!(matches!(ret, Err(SdkError::ConfirmationRequired(_))));
+
+        let confirmed = client.with_confirmation("test_db.prod_table");
+        confirmed.delete_rows(&args).await?;
+        confirmed.drop_table("test_db", "prod_table").await?;
+        client.drop_table("test_db", "other_table").await?;
+        client
+            .with_confirmation("prod_db")
+            .drop_database("prod_db")
+            .await?;
+        assert_eq!(client.dry_run_plan().len(), 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_execute_raw_request() -> Result<()> {
+        let client = dry_run_client(false)?;
+        let ret: CommonResponse = client
+            .execute(
+                reqwest::Method::POST,
+                "/v1/table?desc",
+                Some(serde_json::json!({"database": "test_db", "table": "test_table"})),
+            )
+            .await?;
+        assert_eq!(ret.code, 0);
+        let _: CommonResponse = client
+            .execute(reqwest::Method::POST, "v1/database?list", None)
+            .await?;
+
+        let plan = client.dry_run_plan();
+        assert_eq!(plan[0].path, "/v1/table?desc");
+        assert_eq!(
+            plan[0].body,
+            serde_json::json!({"database": "test_db", "table": "test_table"})
+        );
+        assert_eq!(plan[1].path, "/v1/database?list");
+        assert_eq!(plan[1].body, serde_json::Value::Null);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_execute_checks_protected_objects() -> Result<()> {
+        let (server, client) = crate::mochow::test_client().await?;
+        let config = ClientConfigurationBuilder::default()
+            .account("root")
+            .api_key("fake_api_key")
+            .endpoint(server.endpoint())
+            .protected_tables(vec!["book.book_segments".to_string()])
+            .build()?;
+        let protected = MochowClient::new_with_configuration(&config)?;
+        let drop = serde_json::json!({"database": "book", "table": "book_segments"});
+
+        let ret: Result<CommonResponse, _> = protected
+            .execute(reqwest::Method::DELETE, "/v1/table", Some(drop.clone()))
+            .await;
+        assert!(matches!(ret, Err(SdkError::ConfirmationRequired(_))));
+        let desc = RawRequestBuilder::default()
+            .method(reqwest::Method::POST)
+            .path_and_query("/v1/table?desc")
+            .body(drop.clone())
+            .read_only(true)
+            .build()?;
+        let _: DescriptTableResponse = protected.send(desc).await?;
+        assert!(client.has_table("book", "book_segments").await?);
+
+        let _: CommonResponse = protected
+            .with_confirmation("book.book_segments")
+            .execute(reqwest::Method::DELETE, "/v1/table", Some(drop))
+            .await?;
+        assert!(!client.has_table("book", "book_segments").await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_execute_read_only_requests() -> Result<()> {
+        let server = crate::mochow::testing::FakeMochowServer::start().await?;
+        server.client()?.create_database("test_db").await?;
+        let list = || {
+            RawRequestBuilder::default()
+                .method(reqwest::Method::POST)
+                .path_and_query("/v1/database?list")
+                .clone()
+        };
+
+        // read only requests are sent by a read only client, others are rejected
+        let config = ClientConfigurationBuilder::default()
+            .account("account")
+            .api_key("api_key")
+            .endpoint(server.endpoint())
+            .read_only(true)
+            .build()?;
+        let client = MochowClient::new_with_configuration(&config)?;
+        let ret: ListDatabaseResponse = client.send(list().read_only(true).build()?).await?;
+        assert_eq!(ret.databases, vec!["test_db"]);
+        let ret = client
+            .send::<_, ListDatabaseResponse>(list().build()?)
+            .await;
+        assert!(matches!(ret, Err(SdkError::ReadOnlyError(_))));
+        let ret = client
+            .execute::<CommonResponse>(reqwest::Method::POST, "/v1/database?list", None)
+            .await;
+        assert!(matches!(ret, Err(SdkError::ReadOnlyError(_))));
+        // GET is read only by default and reaches the server
+        let ret = client
+            .execute::<CommonResponse>(reqwest::Method::GET, "/v1/database?list", None)
+            .await;
+        assert!(matches!(ret, Err(SdkError::ServiceError(_))));
+
+        // read only requests are read through in dry run mode and decoded as usual
+        let config = ClientConfigurationBuilder::default()
+            .account("account")
+            .api_key("api_key")
+            .endpoint(server.endpoint())
+            .dry_run(true)
+            .build()?;
+        let client = MochowClient::new_with_configuration(&config)?;
+        let ret: ListDatabaseResponse = client.send(list().read_only(true).build()?).await?;
+        assert_eq!(ret.databases, vec!["test_db"]);
+        assert!(client.dry_run_plan().is_empty());
+        let _: CommonResponse = client.send(list().build()?).await?;
+        assert_eq!(client.dry_run_plan().len(), 1);
+        Ok(())
+    }
+
+    #[derive(Serialize)]
+    struct ListUserArgs {}
+
+    impl IntoRequest for ListUserArgs {
+        fn into_request(
+            self,
+            config: &ClientConfiguration,
+            client: &ClientWithMiddleware,
+        ) -> RequestBuilder {
+            let url = format!("{}/{}/user?list", config.endpoint, config.version);
+            client.post(url).json(&self)
+        }
+
+        fn is_read_only(&self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Serialize)]
+    struct CreateUserArgs {
+        username: String,
+    }
+
+    impl IntoRequest for CreateUserArgs {
+        fn into_request(
+            self,
+            config: &ClientConfiguration,
+            client: &ClientWithMiddleware,
+        ) -> RequestBuilder {
+            let url = format!("{}/{}/user?create", config.endpoint, config.version);
+            client.post(url).json(&self)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_custom_request() -> Result<()> {
+        let client = dry_run_client(false)?;
+        let ret: CommonResponse = client
+            .send(CreateUserArgs {
+                username: "alice".to_string(),
+            })
+            .await?;
+        assert_eq!(ret.code, 0);
+        let ret: Result<CommonResponse, SdkError> = client.send(ListUserArgs {}).await;
+        assert!(matches!(ret, Err(SdkError::DryRunError(_))));
+
+        let plan = client.dry_run_plan();
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].path, "/v1/user?create");
+        assert_eq!(plan[0].body, serde_json::json!({"username": "alice"}));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_checks_protected_objects() -> Result<()> {
+        let config = ClientConfigurationBuilder::default()
+            .account("account")
+            .api_key("api_key")
+            .endpoint("127.0.0.1:1")
+            .dry_run(true)
+            .protected_tables(vec!["test_db.prod_table".to_string()])
+            .build()?;
+        let client = MochowClient::new_with_configuration(&config)?;
+        let drop = DropTableArgsBuilder::default()
+            .database("test_db")
+            .table("prod_table")
+            .build()?;
+        let ret: Result<CommonResponse, SdkError> = client.send(drop.clone()).await;
+        assert!(matches!(ret, Err(SdkError::ConfirmationRequired(_))));
+        let delete = DeleteIndexArgsBuilder::default()
+            .database("test_db")
+            .table("prod_table")
+            .index_name("vector_idx")
+            .build()?;
+        let ret: Result<CommonResponse, SdkError> = client.send(delete).await;
+        assert!(matches!(ret, Err(SdkError::ConfirmationRequired(_))));
+        assert!(client.dry_run_plan().is_empty());
+
+        let _: CommonResponse = client
+            .with_confirmation("test_db.prod_table")
+            .send(drop)
+            .await?;
+        assert_eq!(client.dry_run_plan().len(), 1);
+        Ok(())
+    }
+}
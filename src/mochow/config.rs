@@ -18,10 +18,10 @@ use reqwest::header::{HeaderValue, USER_AGENT};
 /// ```rust
 /// use mochow_rust_sdk::mochow::config::ClientConfigurationBuilder;
 /// let config = ClientConfigurationBuilder::default()
-/// 	.account("your account")
-/// 	.api_key("your api key")
-/// 	.endpoint("http://127.0.0.1:5287")
-/// 	.build().unwrap();
+///     .account("your account")
+///     .api_key("your api key")
+///     .endpoint("http://127.0.0.1:5287")
+///     .build().unwrap();
 /// ```
 #[derive(Debug, Clone, Builder)]
 pub struct ClientConfiguration {
@@ -46,6 +46,42 @@ pub struct ClientConfiguration {
 
     #[builder(default, setter(into))]
     pub user_agent: String,
+
+    /// dry run mode, default is false,
+    /// requests which modify data or schema are recorded instead of sent,
+    /// and a success response is returned, see [crate::mochow::client::MochowClient::dry_run_plan]
+    #[builder(default, setter(into))]
+    pub dry_run: bool,
+
+    /// whether read only requests are still sent to the server in dry run mode, default is true
+    #[builder(default = "true", setter(into))]
+    pub dry_run_read_through: bool,
+
+    /// read only mode, default is false, requests which modify data or schema are rejected
+    #[builder(default, setter(into))]
+    pub read_only: bool,
+
+    /// protected databases, drop_database, drop_table, delete_index and delete_rows by filter
+    /// on them or their tables require a confirmation token,
+    /// see [crate::mochow::client::MochowClient::with_confirmation]
+    #[builder(default, setter(into))]
+    pub protected_databases: Vec<String>,
+
+    /// protected tables in format of "database.table", drop_table, delete_index and delete_rows by filter
+    /// on them require a confirmation token
+    #[builder(default, setter(into))]
+    pub protected_tables: Vec<String>,
+
+    /// validate rows against the schema of the table before insert_row and upsert_row, default is false,
+    /// the offending rows are reported without sending the request,
+    /// see [crate::mochow::api::InsertRowArgs::validate]
+    #[builder(default, setter(into))]
+    pub validate_rows: bool,
+
+    /// how long the schemas described for validation are cached, default is 60 seconds,
+    /// 0 disables the cache, see [crate::mochow::client::MochowClient::cached_desc_table]
+    #[builder(default = "60", setter(into))]
+    pub schema_cache_ttl_seconds: u64,
 }
 
 impl ClientConfiguration {
@@ -0,0 +1,299 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+database and table handles scoped to names and row type
+ */
+use std::marker::PhantomData;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::error::SdkError;
+
+use super::{api::*, client::MochowClient};
+
+/// a database of the client, see [MochowClient::database]
+#[derive(Debug, Clone)]
+pub struct Database {
+    client: MochowClient,
+    name: String,
+}
+
+impl MochowClient {
+    /// handle of the database, no request is sent
+    /// ```rust,ignore
+    /// let db = client.database("book");
+    /// let segments = db.table::<MyRecord>("book_segments");
+    /// let row = segments.get(serde_json::json!({"id": "0001"})).await?;
+    /// ```
+    pub fn database(&self, name: &str) -> Database {
+        Database {
+            client: self.clone(),
+            name: name.to_string(),
+        }
+    }
+}
+
+impl Database {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// handle of the table with rows of type `T`, no request is sent
+    pub fn table<T>(&self, name: &str) -> Table<T> {
+        Table {
+            client: self.client.clone(),
+            database: self.name.clone(),
+            name: name.to_string(),
+            _row: PhantomData,
+        }
+    }
+
+    /// create the database
+    pub async fn create(&self) -> Result<CommonResponse, SdkError> {
+        self.client.create_database(&self.name).await
+    }
+
+    /// drop the database, all tables in the database must be deleted in advance
+    pub async fn drop(&self) -> Result<CommonResponse, SdkError> {
+        self.client.drop_database(&self.name).await
+    }
+
+    /// check if the database exists
+    pub async fn exists(&self) -> Result<bool, SdkError> {
+        self.client.hash_database(&self.name).await
+    }
+
+    /// list tables of the database
+    pub async fn list_tables(&self) -> Result<Vec<String>, SdkError> {
+        Ok(self.client.list_table(&self.name).await?.tables)
+    }
+}
+
+/// a table with rows of type `T`, see [Database::table]
+#[derive(Debug)]
+pub struct Table<T> {
+    client: MochowClient,
+    database: String,
+    name: String,
+    _row: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Table<T> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            database: self.database.clone(),
+            name: self.name.clone(),
+            _row: PhantomData,
+        }
+    }
+}
+
+impl<T> Table<T>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// insert rows
+    pub async fn insert(&self, rows: Vec<T>) -> Result<InsertRowsResponse, SdkError> {
+        let args = InsertRowArgsBuilder::default()
+            .database(&self.database)
+            .table(&self.name)
+            .rows(rows)
+            .build()?;
+        self.client.insert_row(&args).await
+    }
+
+    /// upsert rows, rows with existing primary keys are overwritten as a whole
+    pub async fn upsert(&self, rows: Vec<T>) -> Result<UpsertRowsResponse, SdkError> {
+        let args = UpsertRowArgsBuilder::default()
+            .database(&self.database)
+            .table(&self.name)
+            .rows(rows)
+            .build()?;
+        self.client.upsert_row(&args).await
+    }
+
+    /// get the row by primary key, like `{"id": "0001"}`, with vector fields
+    pub async fn get(&self, primary_key: Value) -> Result<T, SdkError> {
+        let args = QueryRowArgsBuilder::default()
+            .database(&self.database)
+            .table(&self.name)
+            .primary_key(primary_key)
+            .retrieve_vector(true)
+            .build()?;
+        Ok(self.client.query_row::<T>(&args).await?.row)
+    }
+
+    /// delete the row by primary key
+    pub async fn delete(&self, primary_key: Value) -> Result<CommonResponse, SdkError> {
+        let args = DeleteRowArgsBuilder::default()
+            .database(&self.database)
+            .table(&self.name)
+            .primary_key(primary_key)
+            .build()?;
+        self.client.delete_rows(&args).await
+    }
+
+    /// delete rows matching the filter, like "page >= 22"
+    pub async fn delete_by_filter(&self, filter: &str) -> Result<CommonResponse, SdkError> {
+        let args = DeleteRowArgsBuilder::default()
+            .database(&self.database)
+            .table(&self.name)
+            .filter(filter)
+            .build()?;
+        self.client.delete_rows(&args).await
+    }
+
+    /// ann search of vector fields, rows are returned with vector fields
+    pub async fn search(&self, anns: AnnsSearchParams) -> Result<SearchRowsResponse<T>, SdkError> {
+        let args = SearchRowsArgsBuilder::default()
+            .database(&self.database)
+            .table(&self.name)
+            .anns(anns)
+            .retrieve_vector(true)
+            .build()?;
+        self.client.search_rows::<T>(&args).await
+    }
+
+    /// select rows matching the filter, all rows if the filter is none,
+    /// pass the `next_marker` of the last response as marker to get the next page
+    pub async fn select(
+        &self,
+        filter: Option<&str>,
+        marker: Option<Value>,
+        limit: u32,
+    ) -> Result<SelectRowsResponse<T>, SdkError> {
+        let mut builder = SelectRowsArgsBuilder::default();
+        builder
+            .database(&self.database)
+            .table(&self.name)
+            .limit(limit);
+        if let Some(filter) = filter {
+            builder.filter(filter);
+        }
+        if let Some(marker) = marker {
+            builder.marker(marker);
+        }
+        self.client.select_rows::<T>(&builder.build()?).await
+    }
+
+    /// stats of the table
+    pub async fn stats(&self) -> Result<StatsTableResponse, SdkError> {
+        self.client
+            .show_table_stats(&self.database, &self.name)
+            .await
+    }
+
+    /// schema and state of the table
+    pub async fn describe(&self) -> Result<DescriptTable, SdkError> {
+        Ok(self
+            .client
+            .desc_table(&self.database, &self.name)
+            .await?
+            .table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::mochow::testing::FakeMochowServer;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, MochowTable)]
+    struct Book {
+        #[mochow(primary_key, partition_key, not_null)]
+        id: String,
+        #[serde(rename = "bookName")]
+        #[mochow(not_null)]
+        book_name: String,
+        // vector fields are not returned by select
+        #[serde(default)]
+        #[mochow(not_null, dimension = 2)]
+        #[mochow(index(index_type = "FLAT", metric_type = "L2"))]
+        vector: Vec<f64>,
+    }
+
+    fn book(id: &str, x: f64) -> Book {
+        Book {
+            id: id.to_string(),
+            book_name: format!("book {}", id),
+            vector: vec![x, 0.0],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_table_handle() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        let db = client.database("book");
+        db.create().await?;
+        assert!(db.exists().await?);
+        let args = CreateTableArgsBuilder::default()
+            .database(db.name())
+            .table("books")
+            .description("typed handle test")
+            .replication(1_u32)
+            .partition(Partition {
+                partition_type: PartitionType::HASH,
+                partition_num: 1,
+            })
+            .schema(Book::table_schema())
+            .build()?;
+        client.create_table(&args).await?;
+        assert_eq!(db.list_tables().await?, vec!["books"]);
+
+        let books = db.table::<Book>("books");
+        books
+            .insert(vec![book("1", 1.0), book("2", 2.0), book("3", 3.0)])
+            .await?;
+        books.upsert(vec![book("3", 4.0)]).await?;
+        assert_eq!(
+            books.get(serde_json::json!({"id": "3"})).await?,
+            book("3", 4.0)
+        );
+
+        let anns = AnnsSearchParamsBuilder::default()
+            .vector_field("vector")
+            .vector_floats(vec![1.9, 0.0])
+            .params(VectorSearchParams::FLAT(FLATSearchParams {
+                limit: 1,
+                distance_far: None,
+                distance_near: None,
+            }))
+            .build()?;
+        let ret = books.search(anns).await?;
+        assert_eq!(ret.rows[0].row, book("2", 2.0));
+
+        books.delete(serde_json::json!({"id": "1"})).await?;
+        let ret = books.select(Some("bookName != 'book 3'"), None, 10).await?;
+        assert_eq!(ret.rows.len(), 1);
+        assert_eq!(ret.rows[0].id, "2");
+        assert_eq!(books.stats().await?.row_count, 2);
+        assert_eq!(books.describe().await?.schema.fields.len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+idempotent ddl, which return whether an action was taken instead of failing on existence
+ */
+use crate::error::SdkError;
+
+use super::{api::*, client::MochowClient};
+
+// map the error with one of the server codes to Ok(false)
+fn skip_on(
+    ret: Result<CommonResponse, SdkError>,
+    codes: &[ServerErrorCode],
+) -> Result<bool, SdkError> {
+    match ret {
+        Ok(_) => Ok(true),
+        Err(SdkError::ServiceError(e)) if codes.contains(&e.server_code) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+impl MochowClient {
+    /// create the database, false if it already exists
+    pub async fn create_database_if_not_exists(&self, data_base: &str) -> Result<bool, SdkError> {
+        skip_on(
+            self.create_database(data_base).await,
+            &[ServerErrorCode::DB_ALREADY_EXIST],
+        )
+    }
+
+    /// drop the database, false if it does not exist
+    pub async fn drop_database_if_exists(&self, data_base: &str) -> Result<bool, SdkError> {
+        skip_on(
+            self.drop_database(data_base).await,
+            &[ServerErrorCode::DB_NOT_EXIST],
+        )
+    }
+
+    /// create the table, false if it already exists,
+    /// with `verify_schema` the schema of the existing table must match the requested one,
+    /// or [SdkError::ValidationError] is returned with the differences,
+    /// see [TableSchema::verify_matches]
+    pub async fn create_table_if_not_exists(
+        &self,
+        args: &CreateTableArgs,
+        verify_schema: bool,
+    ) -> Result<bool, SdkError> {
+        let created = skip_on(
+            self.create_table(args).await,
+            &[ServerErrorCode::TABLE_ALREADY_EXIST],
+        )?;
+        if !created && verify_schema {
+            let existing = self.desc_table(&args.database, &args.table).await?.table;
+            args.schema.verify_matches(&existing.schema)?;
+        }
+        Ok(created)
+    }
+
+    /// drop the table, false if the table or the database does not exist
+    pub async fn drop_table_if_exists(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<bool, SdkError> {
+        skip_on(
+            self.drop_table(data_base, table).await,
+            &[
+                ServerErrorCode::DB_NOT_EXIST,
+                ServerErrorCode::TABLE_NOT_EXIST,
+            ],
+        )
+    }
+
+    /// create the indexes which do not exist by name, false if all of them already exist,
+    /// the existing indexes are described from the server instead of the schema cache
+    pub async fn create_index_if_not_exists(
+        &self,
+        args: &CreateIndexArgs,
+    ) -> Result<bool, SdkError> {
+        let existing = self
+            .desc_table(&args.database, &args.table)
+            .await?
+            .table
+            .schema
+            .indexes;
+        let indexes: Vec<IndexSchema> = args
+            .indexes
+            .iter()
+            .filter(|index| !existing.iter().any(|x| x.index_name == index.index_name))
+            .cloned()
+            .collect();
+        if indexes.is_empty() {
+            return Ok(false);
+        }
+        let args = CreateIndexArgs {
+            indexes,
+            ..args.clone()
+        };
+        skip_on(
+            self.create_index(&args).await,
+            &[ServerErrorCode::INDEX_ALREADY_EXIST],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::mochow::testing::{self, field, FakeMochowServer};
+
+    fn create_table_args(page_type: FieldType) -> CreateTableArgs {
+        let fields = vec![
+            field("id", FieldType::STRING),
+            field("page", page_type),
+            field("vector", FieldType::FLOAT_VECTOR),
+        ];
+        testing::create_table_args("segments", fields, vec![])
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_ddl() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        assert!(client.create_database_if_not_exists("book").await?);
+        assert!(!client.create_database_if_not_exists("book").await?);
+
+        let args = create_table_args(FieldType::UINT32);
+        assert!(client.create_table_if_not_exists(&args, true).await?);
+        assert!(!client.create_table_if_not_exists(&args, true).await?);
+        let other = create_table_args(FieldType::STRING);
+        assert!(!client.create_table_if_not_exists(&other, false).await?);
+        match client.create_table_if_not_exists(&other, true).await {
+            Err(SdkError::ValidationError(e)) => {
+                assert_eq!(e.violations.len(), 1);
+                assert_eq!(e.violations[0].path, "fields[1].fieldType");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let args = CreateIndexArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .indexes(vec![IndexSchemaBuilder::default()
+                .index_name("vector_idx")
+                .field("vector")
+                .index_type(IndexType::FLAT)
+                .metric_type(MetricType::L2)
+                .build()?])
+            .build()?;
+        assert!(client.create_index_if_not_exists(&args).await?);
+        assert!(!client.create_index_if_not_exists(&args).await?);
+
+        // the index deleted by another client is still in the schema cache
+        client.cached_desc_table("book", "segments").await?;
+        server
+            .client()?
+            .delete_index("book", "segments", "vector_idx")
+            .await?;
+        assert!(client.create_index_if_not_exists(&args).await?);
+
+        client
+            .delete_index("book", "segments", "vector_idx")
+            .await?;
+        assert!(client.drop_table_if_exists("book", "segments").await?);
+        assert!(!client.drop_table_if_exists("book", "segments").await?);
+        assert!(client.drop_database_if_exists("book").await?);
+        assert!(!client.drop_database_if_exists("book").await?);
+        assert!(!client.drop_table_if_exists("book", "segments").await?);
+        Ok(())
+    }
+}
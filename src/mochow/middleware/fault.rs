@@ -0,0 +1,378 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+inject faults into requests for chaos testing, enabled by the `fault-injection` feature
+ */
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use derive_builder::Builder;
+use http::Extensions;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::mochow::api::ServerErrorCode;
+
+/// the fault to inject
+#[derive(Debug, Clone)]
+pub enum Fault {
+    /// delay the request, then send it as usual
+    Latency(Duration),
+    /// send the request to a local listener which resets the connection instead of the server,
+    /// so it fails with a real connection reset which is retried like one
+    ConnectionReset,
+    /// respond with the http status without sending the request, 5xx responses are retried
+    HttpStatus(u16),
+    /// respond with the http status and the mochow error code without sending the request,
+    /// like `ServerCode(503, ServerErrorCode::TABLE_NOT_READY)`
+    ServerCode(u16, ServerErrorCode),
+}
+
+/// when to inject the fault, requests are counted per rule from 1
+#[derive(Debug, Clone)]
+pub enum FaultSchedule {
+    /// every request with the probability in [0, 1]
+    Probability(f64),
+    /// the n-th, 2n-th, 3n-th ... requests
+    EveryNth(u64),
+    /// the i-th request if the i-th item is true, no fault after the end of the sequence
+    Sequence(Vec<bool>),
+}
+
+/// a fault and its schedule
+#[derive(Debug, Builder)]
+pub struct FaultRule {
+    #[builder(setter(into))]
+    pub fault: Fault,
+
+    #[builder(setter(into))]
+    pub schedule: FaultSchedule,
+
+    /// only requests whose path and query contain it are affected, like "/v1/row?search",
+    /// all requests by default
+    #[builder(default, setter(into, strip_option))]
+    pub path: Option<String>,
+
+    #[builder(setter(skip))]
+    requests: AtomicU64,
+}
+
+/// http client middleware injecting latency, connection resets, 5xx responses or mochow error codes
+/// into the requests of [crate::mochow::client::MochowClient] by the rules,
+/// the first rule firing for a request wins, random schedules are reproducible with the same seed
+/// ```rust,no_run
+/// use std::{sync::Arc, time::Duration};
+/// use mochow_sdk_rust::mochow::{api::ServerErrorCode, client::MochowClient, config::ClientConfigurationBuilder};
+/// use mochow_sdk_rust::mochow::middleware::{Fault, FaultInjectionMiddleware, FaultRuleBuilder, FaultSchedule};
+///
+/// let config = ClientConfigurationBuilder::default()
+///     .account("account")
+///     .api_key("api_key")
+///     .endpoint("endpoint")
+///     .build().unwrap();
+/// let faults = FaultInjectionMiddleware::new(42)
+///     .with_rule(
+///         FaultRuleBuilder::default()
+///             .fault(Fault::ServerCode(503, ServerErrorCode::TABLE_NOT_READY))
+///             .schedule(FaultSchedule::Probability(0.1))
+///             .path("/v1/row")
+///             .build().unwrap(),
+///     )
+///     .with_rule(
+///         FaultRuleBuilder::default()
+///             .fault(Fault::Latency(Duration::from_millis(200)))
+///             .schedule(FaultSchedule::EveryNth(10))
+///             .build().unwrap(),
+///     );
+/// let client = MochowClient::new_with_middleware(&config, vec![Arc::new(faults)]).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FaultInjectionMiddleware {
+    rules: Vec<FaultRule>,
+    enabled: AtomicBool,
+    injected: AtomicU64,
+    /// state of the pseudo random generator
+    seed: Mutex<u64>,
+}
+
+impl FaultInjectionMiddleware {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rules: vec![],
+            enabled: AtomicBool::new(true),
+            injected: AtomicU64::new(0),
+            seed: Mutex::new(seed),
+        }
+    }
+
+    pub fn with_rule(mut self, rule: FaultRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// turn on or off the injection at runtime, it's on by default
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// number of faults injected so far
+    pub fn injected(&self) -> u64 {
+        self.injected.load(Ordering::SeqCst)
+    }
+
+    // splitmix64
+    fn next_random(&self) -> f64 {
+        let mut seed = self.seed.lock().unwrap();
+        *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = *seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // the fault to inject for the request, every matched rule counts the request
+    fn pick(&self, path: &str) -> Option<Fault> {
+        if !self.enabled.load(Ordering::SeqCst) {
+            return None;
+        }
+        let mut picked = None;
+        for rule in &self.rules {
+            if rule
+                .path
+                .as_ref()
+                .is_some_and(|p| !path.contains(p.as_str()))
+            {
+                continue;
+            }
+            let n = rule.requests.fetch_add(1, Ordering::SeqCst) + 1;
+            let fire = match &rule.schedule {
+                FaultSchedule::Probability(p) => self.next_random() < *p,
+                FaultSchedule::EveryNth(every) => *every > 0 && n % every == 0,
+                FaultSchedule::Sequence(seq) => seq.get(n as usize - 1).copied().unwrap_or(false),
+            };
+            if fire && picked.is_none() {
+                picked = Some(rule.fault.clone());
+            }
+        }
+        if picked.is_some() {
+            self.injected.fetch_add(1, Ordering::SeqCst);
+        }
+        picked
+    }
+}
+
+fn response(status: u16, code: i32, msg: &str) -> reqwest_middleware::Result<Response> {
+    let resp = http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(json!({"code": code, "msg": msg}).to_string())
+        .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+    Ok(Response::from(resp))
+}
+
+// a local listener which resets the first connection once the request arrives,
+// closing a socket with unread data sends RST instead of FIN
+async fn reset_listener() -> std::io::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        if let Ok((stream, _)) = listener.accept().await {
+            let _ = stream.readable().await;
+        }
+    });
+    Ok(addr)
+}
+
+#[async_trait]
+impl Middleware for FaultInjectionMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let path = match req.url().query() {
+            Some(query) => format!("{}?{}", req.url().path(), query),
+            None => req.url().path().to_string(),
+        };
+        match self.pick(&path) {
+            None => next.run(req, extensions).await,
+            Some(Fault::Latency(delay)) => {
+                tokio::time::sleep(delay).await;
+                next.run(req, extensions).await
+            }
+            Some(Fault::ConnectionReset) => {
+                let addr = reset_listener()
+                    .await
+                    .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+                let mut req = req;
+                let url = req.url_mut();
+                let _ = url.set_scheme("http");
+                let _ = url.set_ip_host(addr.ip());
+                let _ = url.set_port(Some(addr.port()));
+                next.run(req, extensions).await
+            }
+            Some(Fault::HttpStatus(status)) => response(
+                status,
+                ServerErrorCode::INTERNAL_ERROR as i32,
+                "injected fault",
+            ),
+            Some(Fault::ServerCode(status, code)) => {
+                response(status, code as i32, "injected fault")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Instant};
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::{
+        error::SdkError,
+        mochow::{
+            client::MochowClient, config::ClientConfigurationBuilder, testing::FakeMochowServer,
+        },
+    };
+
+    fn rule(fault: Fault, schedule: FaultSchedule) -> FaultRule {
+        FaultRuleBuilder::default()
+            .fault(fault)
+            .schedule(schedule)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_schedules() {
+        let pattern = |seed| {
+            let faults = FaultInjectionMiddleware::new(seed).with_rule(rule(
+                Fault::HttpStatus(500),
+                FaultSchedule::Probability(0.5),
+            ));
+            (0..32)
+                .map(|_| faults.pick("/v1/row?search").is_some())
+                .collect::<Vec<bool>>()
+        };
+        assert_eq!(pattern(7), pattern(7));
+        assert!(pattern(7).contains(&true) && pattern(7).contains(&false));
+
+        let faults = FaultInjectionMiddleware::new(0)
+            .with_rule(rule(Fault::HttpStatus(500), FaultSchedule::EveryNth(3)));
+        let fired: Vec<bool> = (0..6).map(|_| faults.pick("/v1/table").is_some()).collect();
+        assert_eq!(fired, vec![false, false, true, false, false, true]);
+        faults.set_enabled(false);
+        assert!((0..6).all(|_| faults.pick("/v1/table").is_none()));
+        assert_eq!(faults.injected(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_inject_faults() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let faults = Arc::new(
+            FaultInjectionMiddleware::new(0)
+                .with_rule(
+                    FaultRuleBuilder::default()
+                        .fault(Fault::ServerCode(503, ServerErrorCode::TABLE_NOT_READY))
+                        .schedule(FaultSchedule::Sequence(vec![true, false]))
+                        .path("/v1/database?list")
+                        .build()?,
+                )
+                .with_rule(rule(
+                    Fault::ConnectionReset,
+                    FaultSchedule::Sequence(vec![false, false, false, true]),
+                ))
+                .with_rule(rule(
+                    Fault::Latency(Duration::from_millis(50)),
+                    FaultSchedule::EveryNth(1),
+                )),
+        );
+        let config = ClientConfigurationBuilder::default()
+            .account("root")
+            .api_key("fake_api_key")
+            .endpoint(server.endpoint())
+            .max_retries(0_u32)
+            .build()?;
+        let client = MochowClient::new_with_middleware(&config, vec![faults.clone()])?;
+
+        match client.list_database().await {
+            Err(SdkError::ServiceError(e)) => {
+                assert_eq!(e.status_code, 503);
+                assert!(matches!(e.server_code, ServerErrorCode::TABLE_NOT_READY));
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        let start = Instant::now();
+        client.list_database().await?;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        client.create_database("book").await?;
+        assert!(matches!(
+            client.create_database("book").await,
+            Err(SdkError::RequestMiddlewareError(_))
+        ));
+        assert_eq!(faults.injected(), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connection_reset_is_retried() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = |resets: usize, max_retries: u32| -> Result<_> {
+            let faults = Arc::new(FaultInjectionMiddleware::new(0).with_rule(rule(
+                Fault::ConnectionReset,
+                FaultSchedule::Sequence(vec![true; resets]),
+            )));
+            let config = ClientConfigurationBuilder::default()
+                .account("root")
+                .api_key("fake_api_key")
+                .endpoint(server.endpoint())
+                .max_retries(max_retries)
+                .build()?;
+            Ok((
+                MochowClient::new_with_middleware(&config, vec![faults.clone()])?,
+                faults,
+            ))
+        };
+
+        // every attempt is reset, the request and its retry
+        let (client1, faults) = client(3, 1)?;
+        assert!(matches!(
+            client1.list_database().await,
+            Err(SdkError::RequestMiddlewareError(
+                reqwest_middleware::Error::Reqwest(_)
+            ))
+        ));
+        assert_eq!(faults.injected(), 2);
+
+        // the retry after a reset succeeds
+        let (client2, faults) = client(1, 1)?;
+        client2.list_database().await?;
+        assert_eq!(faults.injected(), 1);
+        Ok(())
+    }
+}
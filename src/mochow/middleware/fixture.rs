@@ -0,0 +1,295 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+record and replay http exchanges with fixture files
+ */
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use http::Extensions;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::SdkError, mochow::client::DryRunRequest};
+
+/// mode of the [FixtureMiddleware]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// send requests to the server and save the exchanges to the fixture file
+    Record,
+    /// serve responses from the fixture file, nothing is sent
+    Replay,
+}
+
+/// a recorded request and its response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureEntry {
+    pub method: String,
+    /// path with query, like /v1/table?create
+    pub path: String,
+    /// json body of the request, null if empty
+    pub body: Value,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// json body of the response, or the raw text if it's not json
+    pub response: Value,
+}
+
+impl FixtureEntry {
+    fn matches(&self, req: &DryRunRequest) -> bool {
+        // json objects are compared regardless of the order of keys
+        self.method == req.method && self.path == req.path && self.body == req.body
+    }
+}
+
+/// http client middleware recording request/response pairs to a json fixture file in record mode,
+/// and serving them back in replay mode, requests are matched by method, path, query and json body,
+/// identical requests are replayed in the recorded order,
+/// an unmatched request fails with [SdkError::RequestMiddlewareError]
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use mochow_sdk_rust::mochow::{client::MochowClient, config::ClientConfigurationBuilder};
+/// use mochow_sdk_rust::mochow::middleware::FixtureMiddleware;
+///
+/// let config = ClientConfigurationBuilder::default()
+///     .account("account")
+///     .api_key("api_key")
+///     .endpoint("endpoint")
+///     .build().unwrap();
+/// let fixture = FixtureMiddleware::replay("tests/fixtures/list_database.json").unwrap();
+/// let client = MochowClient::new_with_middleware(&config, vec![Arc::new(fixture)]).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FixtureMiddleware {
+    mode: FixtureMode,
+    path: PathBuf,
+    /// recorded entries, with a flag of whether the entry has been replayed
+    entries: Mutex<Vec<(FixtureEntry, bool)>>,
+}
+
+impl FixtureMiddleware {
+    /// record the exchanges to the file, an existing file is overwritten
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            mode: FixtureMode::Record,
+            path: path.as_ref().to_path_buf(),
+            entries: Mutex::new(vec![]),
+        }
+    }
+
+    /// replay the exchanges recorded in the file
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, SdkError> {
+        let content = std::fs::read(path.as_ref()).map_err(|e| {
+            SdkError::ParamsError(format!(
+                "read fixture file {} failed: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })?;
+        let entries: Vec<FixtureEntry> =
+            serde_json::from_slice(&content).map_err(|e| SdkError::OtherError(e.into()))?;
+        Ok(Self {
+            mode: FixtureMode::Replay,
+            path: path.as_ref().to_path_buf(),
+            entries: Mutex::new(entries.into_iter().map(|e| (e, false)).collect()),
+        })
+    }
+
+    pub fn mode(&self) -> FixtureMode {
+        self.mode
+    }
+
+    /// the recorded entries
+    pub fn entries(&self) -> Vec<FixtureEntry> {
+        let entries = self.entries.lock().unwrap();
+        entries.iter().map(|(e, _)| e.clone()).collect()
+    }
+
+    /// the entries not replayed yet
+    pub fn unused_entries(&self) -> Vec<FixtureEntry> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .filter(|(_, used)| !used)
+            .map(|(e, _)| e.clone())
+            .collect()
+    }
+
+    fn save(&self, entries: &[(FixtureEntry, bool)]) -> anyhow::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let entries: Vec<&FixtureEntry> = entries.iter().map(|(e, _)| e).collect();
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&entries)?)?;
+        Ok(())
+    }
+
+    async fn record_exchange(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let request = DryRunRequest::from_request(&req);
+        let res = next.run(req, extensions).await?;
+        let status = res.status();
+        let headers = res.headers().clone();
+        let bytes = res.bytes().await?;
+        let entry = FixtureEntry {
+            method: request.method,
+            path: request.path,
+            body: request.body,
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+                .collect(),
+            response: serde_json::from_slice(&bytes)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into())),
+        };
+        {
+            let mut entries = self.entries.lock().unwrap();
+            entries.push((entry, false));
+            self.save(&entries)
+                .map_err(reqwest_middleware::Error::Middleware)?;
+        }
+        let mut builder = http::Response::builder().status(status);
+        for (k, v) in headers.iter() {
+            builder = builder.header(k, v);
+        }
+        let resp = builder
+            .body(bytes)
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+        Ok(Response::from(resp))
+    }
+
+    fn replay_exchange(&self, req: Request) -> reqwest_middleware::Result<Response> {
+        let request = DryRunRequest::from_request(&req);
+        let mut entries = self.entries.lock().unwrap();
+        let Some((entry, used)) = entries
+            .iter_mut()
+            .find(|(entry, used)| !used && entry.matches(&request))
+        else {
+            return Err(reqwest_middleware::Error::Middleware(anyhow::anyhow!(
+                "no fixture in {} matches request {}",
+                self.path.display(),
+                request
+            )));
+        };
+        *used = true;
+        let body = match &entry.response {
+            Value::String(text) => text.clone().into_bytes(),
+            other => serde_json::to_vec(other)
+                .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?,
+        };
+        let mut builder = http::Response::builder().status(entry.status);
+        for (k, v) in &entry.headers {
+            // the body may be reencoded, so the length is recomputed
+            if !k.eq_ignore_ascii_case("content-length") {
+                builder = builder.header(k, v);
+            }
+        }
+        let resp = builder
+            .body(body)
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+        Ok(Response::from(resp))
+    }
+}
+
+#[async_trait]
+impl Middleware for FixtureMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        match self.mode {
+            FixtureMode::Record => self.record_exchange(req, extensions, next).await,
+            FixtureMode::Replay => self.replay_exchange(req),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::mochow::{
+        api::ServerErrorCode, client::MochowClient, config::ClientConfigurationBuilder,
+        testing::FakeMochowServer,
+    };
+
+    fn client(endpoint: &str, fixture: Arc<FixtureMiddleware>) -> Result<MochowClient> {
+        let config = ClientConfigurationBuilder::default()
+            .account("root")
+            .api_key("fake_api_key")
+            .endpoint(endpoint)
+            .build()?;
+        Ok(MochowClient::new_with_middleware(&config, vec![fixture])?)
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "mochow_fixture_{}_{}.json",
+            std::process::id(),
+            line!()
+        ));
+        let server = FakeMochowServer::start().await?;
+        let fixture = Arc::new(FixtureMiddleware::record(&path));
+        let recorder = client(&server.endpoint(), fixture.clone())?;
+        recorder.create_database("book").await?;
+        assert!(recorder.create_database("book").await.is_err());
+        assert_eq!(recorder.list_database().await?.databases, vec!["book"]);
+        assert_eq!(fixture.entries().len(), 3);
+        drop(server);
+
+        // nothing listens on the endpoint, all responses come from the fixture file
+        let fixture = Arc::new(FixtureMiddleware::replay(&path)?);
+        let player = client("127.0.0.1:1", fixture.clone())?;
+        player.create_database("book").await?;
+        match player.create_database("book").await {
+            Err(SdkError::ServiceError(e)) => {
+                assert!(matches!(e.server_code, ServerErrorCode::DB_ALREADY_EXIST))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert_eq!(player.list_database().await?.databases, vec!["book"]);
+        assert!(fixture.unused_entries().is_empty());
+
+        // exhausted or unknown requests fail loudly
+        assert!(matches!(
+            player.list_database().await,
+            Err(SdkError::RequestMiddlewareError(_))
+        ));
+        assert!(matches!(
+            player.create_database("other").await,
+            Err(SdkError::RequestMiddlewareError(_))
+        ));
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
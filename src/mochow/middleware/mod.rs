@@ -0,0 +1,24 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+http client middlewares for [crate::mochow::client::MochowClient::new_with_middleware]
+ */
+#[cfg(any(test, feature = "fault-injection"))]
+mod fault;
+mod fixture;
+
+#[cfg(any(test, feature = "fault-injection"))]
+pub use fault::{Fault, FaultInjectionMiddleware, FaultRule, FaultRuleBuilder, FaultSchedule};
+pub use fixture::{FixtureEntry, FixtureMiddleware, FixtureMode};
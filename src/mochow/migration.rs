@@ -0,0 +1,431 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+declarative schema migration, diff the desired schema against the existing table and apply the steps
+ */
+use std::{fmt::Display, time::Duration};
+
+use crate::error::SdkError;
+
+use super::{
+    api::*,
+    client::MochowClient,
+    validation::{ValidationError, Violation, Violations},
+};
+
+/// a ddl step of the [MigrationPlan]
+#[derive(Debug, Clone)]
+pub enum MigrationStep {
+    AddField(FieldSchema),
+    /// delete the index, it's followed by a create index step if the definition is changed
+    DeleteIndex(String),
+    CreateIndex(IndexSchema),
+    /// update auto build and auto build policy of the index
+    ModifyIndex(IndexSchema),
+}
+
+impl Display for MigrationStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationStep::AddField(field) => {
+                write!(f, "add field {} {:?}", field.field_name, field.field_type)
+            }
+            MigrationStep::DeleteIndex(name) => write!(f, "delete index {}", name),
+            MigrationStep::CreateIndex(index) => write!(
+                f,
+                "create index {} {:?} on {}",
+                index.index_name, index.index_type, index.field
+            ),
+            MigrationStep::ModifyIndex(index) => write!(
+                f,
+                "modify index {} auto build {}",
+                index.index_name, index.auto_build
+            ),
+        }
+    }
+}
+
+/// steps to migrate a table to the desired schema, see [MochowClient::plan_migration]
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    pub database: String,
+    pub table: String,
+    pub steps: Vec<MigrationStep>,
+    /// changes which are impossible in place, like field type, primary key or dimension changes,
+    /// the plan can't be applied unless it's empty
+    pub conflicts: Vec<Violation>,
+}
+
+impl MigrationPlan {
+    /// diff the desired schema against the current one, fields and indexes are matched by name
+    pub fn diff(
+        data_base: &str,
+        table: &str,
+        current: &TableSchema,
+        desired: &TableSchema,
+    ) -> Self {
+        let mut steps = vec![];
+        let mut conflicts = Violations::default();
+        for (i, field) in desired.fields.iter().enumerate() {
+            let path = format!("fields[{}]", i);
+            let Some(existing) = current
+                .fields
+                .iter()
+                .find(|f| f.field_name == field.field_name)
+            else {
+                if field.primary_key
+                    || field.partition_key
+                    || field.not_null
+                    || field.field_type == FieldType::FLOAT_VECTOR
+                {
+                    conflicts.add(
+                        path,
+                        format!(
+                            "field {} can't be added, only nullable scalar fields are supported",
+                            field.field_name
+                        ),
+                    );
+                } else {
+                    steps.push(MigrationStep::AddField(field.clone()));
+                }
+                continue;
+            };
+            let mut changed = |name: &str, from: String, to: String| {
+                if from != to {
+                    conflicts.add(
+                        format!("{}.{}", path, name),
+                        format!("can't be changed from {} to {} in place", from, to),
+                    );
+                }
+            };
+            changed(
+                "fieldType",
+                format!("{:?}", existing.field_type),
+                format!("{:?}", field.field_type),
+            );
+            changed(
+                "primaryKey",
+                existing.primary_key.to_string(),
+                field.primary_key.to_string(),
+            );
+            changed(
+                "partitionKey",
+                existing.partition_key.to_string(),
+                field.partition_key.to_string(),
+            );
+            changed(
+                "autoIncrement",
+                existing.auto_increment.to_string(),
+                field.auto_increment.to_string(),
+            );
+            changed(
+                "notNull",
+                existing.not_null.to_string(),
+                field.not_null.to_string(),
+            );
+            changed(
+                "dimension",
+                format!("{:?}", existing.dimension),
+                format!("{:?}", field.dimension),
+            );
+        }
+        for field in &current.fields {
+            if !desired
+                .fields
+                .iter()
+                .any(|f| f.field_name == field.field_name)
+            {
+                conflicts.add(
+                    "fields",
+                    format!("field {} can't be removed", field.field_name),
+                );
+            }
+        }
+
+        let mut deletes = vec![];
+        let mut creates = vec![];
+        let mut modifies = vec![];
+        for index in &current.indexes {
+            if !desired
+                .indexes
+                .iter()
+                .any(|x| x.index_name == index.index_name)
+            {
+                deletes.push(MigrationStep::DeleteIndex(index.index_name.clone()));
+            }
+        }
+        for index in &desired.indexes {
+            match current
+                .indexes
+                .iter()
+                .find(|x| x.index_name == index.index_name)
+            {
+                None => creates.push(MigrationStep::CreateIndex(index.clone())),
+                Some(existing) if !same_definition(existing, index) => {
+                    deletes.push(MigrationStep::DeleteIndex(index.index_name.clone()));
+                    creates.push(MigrationStep::CreateIndex(index.clone()));
+                }
+                Some(existing) if !same_auto_build(existing, index) => {
+                    modifies.push(MigrationStep::ModifyIndex(index.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        steps.extend(deletes);
+        steps.extend(creates);
+        steps.extend(modifies);
+        Self {
+            database: data_base.to_string(),
+            table: table.to_string(),
+            steps,
+            conflicts: conflicts
+                .into_result()
+                .err()
+                .map(|e| e.violations)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// no step and no conflict, the table is up to date
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty() && self.conflicts.is_empty()
+    }
+}
+
+impl Display for MigrationPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "migration of table {}.{}", self.database, self.table)?;
+        for step in &self.steps {
+            writeln!(f, "  {}", step)?;
+        }
+        for conflict in &self.conflicts {
+            writeln!(f, "  conflict {}", conflict)?;
+        }
+        Ok(())
+    }
+}
+
+// index definition which can't be changed without recreating the index
+fn same_definition(a: &IndexSchema, b: &IndexSchema) -> bool {
+    a.field == b.field
+        && a.index_type == b.index_type
+        && a.metric_type == b.metric_type
+        && serde_json::to_value(params(a)).ok() == serde_json::to_value(params(b)).ok()
+}
+
+// empty params of FLAT index are the same as none
+fn params(index: &IndexSchema) -> Option<&VectorIndexParams> {
+    index
+        .params
+        .as_ref()
+        .filter(|params| !matches!(params, VectorIndexParams::FLAT(_)))
+}
+
+fn same_auto_build(a: &IndexSchema, b: &IndexSchema) -> bool {
+    a.auto_build == b.auto_build
+        && serde_json::to_value(&a.auto_build_policy).ok()
+            == serde_json::to_value(&b.auto_build_policy).ok()
+}
+
+impl MochowClient {
+    /// plan the migration of the table to the desired schema by diffing it against desc_table
+    pub async fn plan_migration(
+        &self,
+        data_base: &str,
+        table: &str,
+        desired: &TableSchema,
+    ) -> Result<MigrationPlan, SdkError> {
+        let current = self.desc_table(data_base, table).await?.table.schema;
+        Ok(MigrationPlan::diff(data_base, table, &current, desired))
+    }
+
+    /// apply the steps of the plan in order, wait for the deleted indexes to disappear
+    /// and the created indexes to be built, the timeout is for each index, a plan with conflicts is rejected
+    /// with [SdkError::ValidationError] before any step
+    /// ```rust,ignore
+    /// let plan = client.plan_migration("book", "book_segments", &Book::table_schema()).await?;
+    /// println!("{}", plan);
+    /// client.apply_migration(&plan, Duration::from_secs(600)).await?;
+    /// ```
+    pub async fn apply_migration(
+        &self,
+        plan: &MigrationPlan,
+        index_timeout: Duration,
+    ) -> Result<(), SdkError> {
+        if !plan.conflicts.is_empty() {
+            return Err(ValidationError {
+                violations: plan.conflicts.clone(),
+            }
+            .into());
+        }
+        let (data_base, table) = (plan.database.as_str(), plan.table.as_str());
+        for step in &plan.steps {
+            match step {
+                MigrationStep::AddField(field) => {
+                    let args = AddFieldArgsBuilder::default()
+                        .database(data_base)
+                        .table(table)
+                        .schema(TableSchema {
+                            fields: vec![field.clone()],
+                            indexes: vec![],
+                        })
+                        .build()?;
+                    self.add_field(&args).await?;
+                }
+                MigrationStep::DeleteIndex(name) => {
+                    self.delete_index(data_base, table, name).await?;
+                    self.wait_index_deleted(data_base, table, name, index_timeout)
+                        .await?;
+                }
+                MigrationStep::CreateIndex(index) => {
+                    let args = CreateIndexArgsBuilder::default()
+                        .database(data_base)
+                        .table(table)
+                        .indexes(vec![index.clone()])
+                        .build()?;
+                    self.create_index(&args).await?;
+                    // an index of the same name is already gone, any major version is of the new one
+                    self.wait_index_built(data_base, table, &index.index_name, None, index_timeout)
+                        .await?;
+                }
+                MigrationStep::ModifyIndex(index) => {
+                    let args = ModifyIndexArgsBuilder::default()
+                        .database(data_base)
+                        .table(table)
+                        .index(index.clone())
+                        .build()?;
+                    self.modify_index(&args).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::mochow::testing::{create_table_args, field, FakeMochowServer};
+
+    fn hnsw(m: u32) -> IndexSchema {
+        IndexSchemaBuilder::default()
+            .index_name("vector_idx")
+            .field("vector")
+            .index_type(IndexType::HNSW)
+            .metric_type(MetricType::L2)
+            .params(VectorIndexParams::HNSW(HNSWIndexParam {
+                m,
+                ef_construction: 200,
+            }))
+            .build()
+            .unwrap()
+    }
+
+    fn page_idx() -> IndexSchema {
+        IndexSchemaBuilder::default()
+            .index_name("page_idx")
+            .field("page")
+            .index_type(IndexType::SECONDARY_INDEX)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_migration() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        client.create_database("book").await?;
+        let args = create_table_args(
+            "segments",
+            vec![
+                field("id", FieldType::STRING),
+                field("page", FieldType::UINT32),
+                field("vector", FieldType::FLOAT_VECTOR),
+            ],
+            vec![hnsw(16), page_idx()],
+        );
+        client.create_table(&args).await?;
+
+        let mut desired = TableSchema {
+            fields: vec![
+                field("id", FieldType::STRING),
+                field("page", FieldType::UINT32),
+                field("vector", FieldType::FLOAT_VECTOR),
+                field("author", FieldType::STRING),
+            ],
+            indexes: vec![hnsw(32)],
+        };
+        desired.indexes[0].auto_build = true;
+        let plan = client.plan_migration("book", "segments", &desired).await?;
+        let steps: Vec<String> = plan.steps.iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            steps,
+            vec![
+                "add field author STRING",
+                "delete index page_idx",
+                "delete index vector_idx",
+                "create index vector_idx Some(HNSW) on vector",
+            ]
+        );
+        assert!(plan.conflicts.is_empty());
+        // vector_idx is re-created only after the deleted one disappears
+        server.set_index_delete_polls(2);
+        server.set_index_build_polls(2);
+        client
+            .apply_migration(&plan, Duration::from_secs(5))
+            .await?;
+        let index = client
+            .desc_index("book", "segments", "vector_idx")
+            .await?
+            .index;
+        assert!(matches!(index.state, Some(IndexState::NORMAL)));
+        assert!(client
+            .plan_migration("book", "segments", &desired)
+            .await?
+            .is_empty());
+
+        // only the auto build is changed
+        desired.indexes[0].auto_build = false;
+        let plan = client.plan_migration("book", "segments", &desired).await?;
+        assert!(matches!(
+            plan.steps.as_slice(),
+            [MigrationStep::ModifyIndex(_)]
+        ));
+        client
+            .apply_migration(&plan, Duration::from_secs(5))
+            .await?;
+        assert!(client
+            .plan_migration("book", "segments", &desired)
+            .await?
+            .is_empty());
+
+        desired.fields[1] = field("page", FieldType::STRING);
+        desired.fields[2].dimension = Some(4);
+        desired.fields.pop();
+        let plan = client.plan_migration("book", "segments", &desired).await?;
+        let paths: Vec<&str> = plan.conflicts.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["fields[1].fieldType", "fields[2].dimension", "fields"]
+        );
+        assert!(matches!(
+            client.apply_migration(&plan, Duration::from_secs(5)).await,
+            Err(SdkError::ValidationError(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,560 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+abstraction of all mochow operations, so that application code can depend on a trait object
+instead of the concrete client
+ */
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::error::SdkError;
+
+use super::{api::*, client::MochowClient};
+
+/// all database, table, index and row operations of mochow,
+/// implemented by [MochowClient], you can substitute mocks, decorators or other backends
+/// behind `Arc<dyn MochowApi>`, rows are represented by [serde_json::Value] to keep the trait object safe
+/// ```rust,ignore
+/// use std::sync::Arc;
+/// use mochow_sdk_rust::mochow::{client::MochowClient, mochow_api::MochowApi};
+///
+/// async fn count_tables(api: Arc<dyn MochowApi>, database: &str) -> usize {
+///     api.list_table(database).await.map(|ret| ret.tables.len()).unwrap_or(0)
+/// }
+///
+/// let client = MochowClient::new("account", "api_key", "endpoint").unwrap();
+/// let count = count_tables(Arc::new(client), "book").await;
+/// ```
+#[async_trait]
+pub trait MochowApi: Send + Sync {
+    /// create a database
+    async fn create_database(&self, data_base: &str) -> Result<CommonResponse, SdkError>;
+
+    /// drop a database, all tables in the database must be deleted in advance
+    async fn drop_database(&self, data_base: &str) -> Result<CommonResponse, SdkError>;
+
+    /// list all databases
+    async fn list_database(&self) -> Result<ListDatabaseResponse, SdkError>;
+
+    /// check if the database exists
+    async fn has_database(&self, data_base: &str) -> Result<bool, SdkError>;
+
+    /// create table
+    async fn create_table(&self, args: &CreateTableArgs) -> Result<CommonResponse, SdkError>;
+
+    /// drop table
+    async fn drop_table(&self, data_base: &str, table: &str) -> Result<CommonResponse, SdkError>;
+
+    /// list table
+    async fn list_table(&self, data_base: &str) -> Result<ListTableResponse, SdkError>;
+
+    /// check if the table exists
+    async fn has_table(&self, data_base: &str, table: &str) -> Result<bool, SdkError>;
+
+    /// descript table
+    async fn desc_table(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<DescriptTableResponse, SdkError>;
+
+    /// add field for table
+    async fn add_field(&self, args: &AddFieldArgs) -> Result<CommonResponse, SdkError>;
+
+    /// show table stats
+    async fn show_table_stats(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<StatsTableResponse, SdkError>;
+
+    /// alias table
+    async fn alias_table(
+        &self,
+        data_base: &str,
+        table: &str,
+        alias: &str,
+    ) -> Result<CommonResponse, SdkError>;
+
+    /// unalias table
+    async fn unalias_table(
+        &self,
+        data_base: &str,
+        table: &str,
+        alias: &str,
+    ) -> Result<CommonResponse, SdkError>;
+
+    /// create index
+    async fn create_index(&self, args: &CreateIndexArgs) -> Result<CommonResponse, SdkError>;
+
+    /// descript index
+    async fn desc_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+    ) -> Result<DescriptIndexResponse, SdkError>;
+
+    /// modify index, only support 'autobuild' attribute
+    async fn modify_index(&self, args: &ModifyIndexArgs) -> Result<CommonResponse, SdkError>;
+
+    /// rebuild vector index
+    async fn rebuild_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+    ) -> Result<CommonResponse, SdkError>;
+
+    /// delete index
+    async fn delete_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+    ) -> Result<CommonResponse, SdkError>;
+
+    /// insert rows
+    async fn insert_row(&self, args: &InsertRowArgs<Value>)
+        -> Result<InsertRowsResponse, SdkError>;
+
+    /// upsert rows
+    async fn upsert_row(&self, args: &UpsertRowArgs<Value>)
+        -> Result<UpsertRowsResponse, SdkError>;
+
+    /// update the scalar fields of a row
+    async fn update_row(&self, args: &UpdateRowArgs) -> Result<CommonResponse, SdkError>;
+
+    /// delete rows by primary key or filter
+    async fn delete_rows(&self, args: &DeleteRowArgs) -> Result<CommonResponse, SdkError>;
+
+    /// query single row by primary key
+    async fn query_row(&self, args: &QueryRowArgs) -> Result<QueryRowsResponse<Value>, SdkError>;
+
+    /// ann search of vector fields
+    async fn search_rows(
+        &self,
+        args: &SearchRowsArgs,
+    ) -> Result<SearchRowsResponse<Value>, SdkError>;
+
+    /// filter rows by scalar fields
+    async fn select_rows(
+        &self,
+        args: &SelectRowsArgs,
+    ) -> Result<SelectRowsResponse<Value>, SdkError>;
+
+    /// batch ann search of vector fields
+    async fn batch_search_rows(
+        &self,
+        args: &BatchSearchRowsArgs,
+    ) -> Result<BatchSearchRowsResponse<Value>, SdkError>;
+}
+
+#[async_trait]
+impl MochowApi for MochowClient {
+    async fn create_database(&self, data_base: &str) -> Result<CommonResponse, SdkError> {
+        MochowClient::create_database(self, data_base).await
+    }
+
+    async fn drop_database(&self, data_base: &str) -> Result<CommonResponse, SdkError> {
+        MochowClient::drop_database(self, data_base).await
+    }
+
+    async fn list_database(&self) -> Result<ListDatabaseResponse, SdkError> {
+        MochowClient::list_database(self).await
+    }
+
+    async fn has_database(&self, data_base: &str) -> Result<bool, SdkError> {
+        MochowClient::hash_database(self, data_base).await
+    }
+
+    async fn create_table(&self, args: &CreateTableArgs) -> Result<CommonResponse, SdkError> {
+        MochowClient::create_table(self, args).await
+    }
+
+    async fn drop_table(&self, data_base: &str, table: &str) -> Result<CommonResponse, SdkError> {
+        MochowClient::drop_table(self, data_base, table).await
+    }
+
+    async fn list_table(&self, data_base: &str) -> Result<ListTableResponse, SdkError> {
+        MochowClient::list_table(self, data_base).await
+    }
+
+    async fn has_table(&self, data_base: &str, table: &str) -> Result<bool, SdkError> {
+        MochowClient::has_table(self, data_base, table).await
+    }
+
+    async fn desc_table(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<DescriptTableResponse, SdkError> {
+        MochowClient::desc_table(self, data_base, table).await
+    }
+
+    async fn add_field(&self, args: &AddFieldArgs) -> Result<CommonResponse, SdkError> {
+        MochowClient::add_field(self, args).await
+    }
+
+    async fn show_table_stats(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<StatsTableResponse, SdkError> {
+        MochowClient::show_table_stats(self, data_base, table).await
+    }
+
+    async fn alias_table(
+        &self,
+        data_base: &str,
+        table: &str,
+        alias: &str,
+    ) -> Result<CommonResponse, SdkError> {
+        MochowClient::alias_table(self, data_base, table, alias).await
+    }
+
+    async fn unalias_table(
+        &self,
+        data_base: &str,
+        table: &str,
+        alias: &str,
+    ) -> Result<CommonResponse, SdkError> {
+        MochowClient::unalias_table(self, data_base, table, alias).await
+    }
+
+    async fn create_index(&self, args: &CreateIndexArgs) -> Result<CommonResponse, SdkError> {
+        MochowClient::create_index(self, args).await
+    }
+
+    async fn desc_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+    ) -> Result<DescriptIndexResponse, SdkError> {
+        MochowClient::desc_index(self, data_base, table, index_name).await
+    }
+
+    async fn modify_index(&self, args: &ModifyIndexArgs) -> Result<CommonResponse, SdkError> {
+        MochowClient::modify_index(self, args).await
+    }
+
+    async fn rebuild_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+    ) -> Result<CommonResponse, SdkError> {
+        MochowClient::rebuild_index(self, data_base, table, index_name).await
+    }
+
+    async fn delete_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+    ) -> Result<CommonResponse, SdkError> {
+        MochowClient::delete_index(self, data_base, table, index_name).await
+    }
+
+    async fn insert_row(
+        &self,
+        args: &InsertRowArgs<Value>,
+    ) -> Result<InsertRowsResponse, SdkError> {
+        MochowClient::insert_row(self, args).await
+    }
+
+    async fn upsert_row(
+        &self,
+        args: &UpsertRowArgs<Value>,
+    ) -> Result<UpsertRowsResponse, SdkError> {
+        MochowClient::upsert_row(self, args).await
+    }
+
+    async fn update_row(&self, args: &UpdateRowArgs) -> Result<CommonResponse, SdkError> {
+        MochowClient::update_row(self, args).await
+    }
+
+    async fn delete_rows(&self, args: &DeleteRowArgs) -> Result<CommonResponse, SdkError> {
+        MochowClient::delete_rows(self, args).await
+    }
+
+    async fn query_row(&self, args: &QueryRowArgs) -> Result<QueryRowsResponse<Value>, SdkError> {
+        MochowClient::query_row(self, args).await
+    }
+
+    async fn search_rows(
+        &self,
+        args: &SearchRowsArgs,
+    ) -> Result<SearchRowsResponse<Value>, SdkError> {
+        MochowClient::search_rows(self, args).await
+    }
+
+    async fn select_rows(
+        &self,
+        args: &SelectRowsArgs,
+    ) -> Result<SelectRowsResponse<Value>, SdkError> {
+        MochowClient::select_rows(self, args).await
+    }
+
+    async fn batch_search_rows(
+        &self,
+        args: &BatchSearchRowsArgs,
+    ) -> Result<BatchSearchRowsResponse<Value>, SdkError> {
+        MochowClient::batch_search_rows(self, args).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::mochow::config::ClientConfigurationBuilder;
+
+    /// a decorator counting the calls of the inner api
+    struct CountingApi {
+        inner: Arc<dyn MochowApi>,
+        calls: AtomicUsize,
+    }
+
+    impl CountingApi {
+        fn count(&self) {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait]
+    impl MochowApi for CountingApi {
+        async fn create_database(&self, data_base: &str) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.create_database(data_base).await
+        }
+
+        async fn drop_database(&self, data_base: &str) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.drop_database(data_base).await
+        }
+
+        async fn list_database(&self) -> Result<ListDatabaseResponse, SdkError> {
+            self.count();
+            self.inner.list_database().await
+        }
+
+        async fn has_database(&self, data_base: &str) -> Result<bool, SdkError> {
+            self.count();
+            self.inner.has_database(data_base).await
+        }
+
+        async fn create_table(&self, args: &CreateTableArgs) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.create_table(args).await
+        }
+
+        async fn drop_table(
+            &self,
+            data_base: &str,
+            table: &str,
+        ) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.drop_table(data_base, table).await
+        }
+
+        async fn list_table(&self, data_base: &str) -> Result<ListTableResponse, SdkError> {
+            self.count();
+            self.inner.list_table(data_base).await
+        }
+
+        async fn has_table(&self, data_base: &str, table: &str) -> Result<bool, SdkError> {
+            self.count();
+            self.inner.has_table(data_base, table).await
+        }
+
+        async fn desc_table(
+            &self,
+            data_base: &str,
+            table: &str,
+        ) -> Result<DescriptTableResponse, SdkError> {
+            self.count();
+            self.inner.desc_table(data_base, table).await
+        }
+
+        async fn add_field(&self, args: &AddFieldArgs) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.add_field(args).await
+        }
+
+        async fn show_table_stats(
+            &self,
+            data_base: &str,
+            table: &str,
+        ) -> Result<StatsTableResponse, SdkError> {
+            self.count();
+            self.inner.show_table_stats(data_base, table).await
+        }
+
+        async fn alias_table(
+            &self,
+            data_base: &str,
+            table: &str,
+            alias: &str,
+        ) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.alias_table(data_base, table, alias).await
+        }
+
+        async fn unalias_table(
+            &self,
+            data_base: &str,
+            table: &str,
+            alias: &str,
+        ) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.unalias_table(data_base, table, alias).await
+        }
+
+        async fn create_index(&self, args: &CreateIndexArgs) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.create_index(args).await
+        }
+
+        async fn desc_index(
+            &self,
+            data_base: &str,
+            table: &str,
+            index_name: &str,
+        ) -> Result<DescriptIndexResponse, SdkError> {
+            self.count();
+            self.inner.desc_index(data_base, table, index_name).await
+        }
+
+        async fn modify_index(&self, args: &ModifyIndexArgs) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.modify_index(args).await
+        }
+
+        async fn rebuild_index(
+            &self,
+            data_base: &str,
+            table: &str,
+            index_name: &str,
+        ) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.rebuild_index(data_base, table, index_name).await
+        }
+
+        async fn delete_index(
+            &self,
+            data_base: &str,
+            table: &str,
+            index_name: &str,
+        ) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.delete_index(data_base, table, index_name).await
+        }
+
+        async fn insert_row(
+            &self,
+            args: &InsertRowArgs<Value>,
+        ) -> Result<InsertRowsResponse, SdkError> {
+            self.count();
+            self.inner.insert_row(args).await
+        }
+
+        async fn upsert_row(
+            &self,
+            args: &UpsertRowArgs<Value>,
+        ) -> Result<UpsertRowsResponse, SdkError> {
+            self.count();
+            self.inner.upsert_row(args).await
+        }
+
+        async fn update_row(&self, args: &UpdateRowArgs) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.update_row(args).await
+        }
+
+        async fn delete_rows(&self, args: &DeleteRowArgs) -> Result<CommonResponse, SdkError> {
+            self.count();
+            self.inner.delete_rows(args).await
+        }
+
+        async fn query_row(
+            &self,
+            args: &QueryRowArgs,
+        ) -> Result<QueryRowsResponse<Value>, SdkError> {
+            self.count();
+            self.inner.query_row(args).await
+        }
+
+        async fn search_rows(
+            &self,
+            args: &SearchRowsArgs,
+        ) -> Result<SearchRowsResponse<Value>, SdkError> {
+            self.count();
+            self.inner.search_rows(args).await
+        }
+
+        async fn select_rows(
+            &self,
+            args: &SelectRowsArgs,
+        ) -> Result<SelectRowsResponse<Value>, SdkError> {
+            self.count();
+            self.inner.select_rows(args).await
+        }
+
+        async fn batch_search_rows(
+            &self,
+            args: &BatchSearchRowsArgs,
+        ) -> Result<BatchSearchRowsResponse<Value>, SdkError> {
+            self.count();
+            self.inner.batch_search_rows(args).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_behind_trait_object() -> Result<()> {
+        let config = ClientConfigurationBuilder::default()
+            .account("account")
+            .api_key("api_key")
+            .endpoint("127.0.0.1:1")
+            .dry_run(true)
+            .build()?;
+        let client = MochowClient::new_with_configuration(&config)?;
+        let counting = Arc::new(CountingApi {
+            inner: Arc::new(client.clone()),
+            calls: AtomicUsize::new(0),
+        });
+        let api: Arc<dyn MochowApi> = counting.clone();
+
+        api.create_database("test_db").await?;
+        let args = InsertRowArgsBuilder::default()
+            .database("test_db")
+            .table("test_table")
+            .rows(vec![serde_json::json!({"id": "0001"})])
+            .build()?;
+        api.insert_row(&args).await?;
+        api.drop_database("test_db").await?;
+
+        assert_eq!(counting.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(client.dry_run_plan().len(), 3);
+        Ok(())
+    }
+}
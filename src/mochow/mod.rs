@@ -11,19 +11,121 @@
  * either express or implied. See the License for the specific language governing permissions
  * and limitations under the License.
  */
+pub mod alias;
+
 pub mod api;
 
+mod cache;
+
+pub mod cascade;
+
 #[allow(dead_code)]
 pub mod client;
 
 pub mod config;
 
+pub mod handle;
+
+pub mod idempotent;
+
+pub mod middleware;
+
+pub mod migration;
+
+pub mod mochow_api;
+
+pub mod rebuild;
+
+pub mod schema_file;
+
+#[cfg(any(test, feature = "testing", feature = "memory-backend"))]
+pub mod testing;
+
+pub mod validation;
+
+pub mod wait;
+
 #[cfg(test)]
 lazy_static::lazy_static! {
-    pub static ref TESTACCOUNT: String = "your_account".to_string();
-    pub static ref TESTPASSWORD: String = "your_password".to_string();
-    pub static ref TESTENDPOINT: String = "http://127.0.0.1:5288".to_string();
     pub static ref TESTDATABSE: String = "book".to_string();
     pub static ref TESTTABLE: String = "book_segments".to_string();
-    pub static ref UTCLIENT: crate::mochow::client::MochowClient = crate::mochow::client::MochowClient::new(&TESTACCOUNT, &TESTPASSWORD, &TESTENDPOINT).unwrap();
+}
+
+/// a fake server with the test table of books and its client for the api tests,
+/// the table has a secondary index on bookName, a HNSW index on vector and rows 0001 to 0004
+#[cfg(test)]
+pub(crate) async fn test_client(
+) -> anyhow::Result<(testing::FakeMochowServer, client::MochowClient)> {
+    use api::*;
+
+    let server = testing::FakeMochowServer::start().await?;
+    let client = server.client()?;
+    client.create_database(&TESTDATABSE).await?;
+    let field = |name: &str, field_type: FieldType| {
+        FieldSchemaBuilder::default()
+            .field_name(name)
+            .field_type(field_type)
+            .primary_key(name == "id")
+            .partition_key(name == "id")
+            .not_null(name == "id" || name == "bookName" || name == "vector")
+            .dimension(if name == "vector" { Some(3) } else { None })
+            .build()
+    };
+    let args = CreateTableArgsBuilder::default()
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
+        .description("basic test")
+        .replication(3_u32)
+        .partition(Partition {
+            partition_type: PartitionType::HASH,
+            partition_num: 3,
+        })
+        .schema(TableSchema {
+            fields: vec![
+                field("id", FieldType::STRING)?,
+                field("bookName", FieldType::STRING)?,
+                field("author", FieldType::STRING)?,
+                field("page", FieldType::UINT32)?,
+                field("vector", FieldType::FLOAT_VECTOR)?,
+            ],
+            indexes: vec![
+                IndexSchemaBuilder::default()
+                    .index_name("book_name_idx")
+                    .field("bookName")
+                    .index_type(IndexType::SECONDARY_INDEX)
+                    .build()?,
+                IndexSchemaBuilder::default()
+                    .index_name("vector_idx")
+                    .field("vector")
+                    .index_type(IndexType::HNSW)
+                    .metric_type(MetricType::L2)
+                    .params(VectorIndexParams::HNSW(HNSWIndexParam {
+                        m: 32,
+                        ef_construction: 200,
+                    }))
+                    .build()?,
+            ],
+        })
+        .build()?;
+    client.create_table(&args).await?;
+    let books = [("西游记", "吴承恩"), ("三国演义", "罗贯中")];
+    let rows: Vec<serde_json::Value> = (1..=4)
+        .map(|i| {
+            let (book_name, author) = books[(i - 1) / 2];
+            serde_json::json!({
+                "id": format!("{:04}", i),
+                "bookName": book_name,
+                "author": author,
+                "page": 20 + i,
+                "vector": [0.2123, 0.2 + i as f64 / 100.0, 0.213],
+            })
+        })
+        .collect();
+    let args = UpsertRowArgsBuilder::default()
+        .database(TESTDATABSE.to_string())
+        .table(TESTTABLE.to_string())
+        .rows(rows)
+        .build()?;
+    client.upsert_row(&args).await?;
+    Ok((server, client))
 }
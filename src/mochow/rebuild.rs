@@ -0,0 +1,318 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+blue/green rebuild of a table into a shadow table, readers are moved by switching an alias
+ */
+use std::time::Duration;
+
+use derive_builder::Builder;
+
+use crate::error::SdkError;
+
+use super::{api::*, client::MochowClient};
+
+/// args of [MochowClient::rebuild_table]
+#[derive(Debug, Clone, Builder)]
+pub struct RebuildTableArgs {
+    #[builder(setter(into))]
+    pub database: String,
+    /// the table to rebuild
+    #[builder(setter(into))]
+    pub table: String,
+    /// the shadow table to create
+    #[builder(setter(into))]
+    pub new_table: String,
+    /// the alias readers use, moved from the old table to the new one
+    #[builder(setter(into))]
+    pub alias: String,
+    /// schema of the new table, the schema of the old table by default
+    #[builder(default, setter(strip_option))]
+    pub schema: Option<TableSchema>,
+    /// rows of every select and upsert, the server accepts at most 1000 rows per upsert
+    #[builder(default = "1000")]
+    pub batch_size: u32,
+    /// drop the old table after the alias is moved, the other aliases of it are moved too
+    #[builder(default)]
+    pub drop_old_table: bool,
+    /// allow dropping the old table with dynamic fields enabled, they are not copied and lost
+    #[builder(default)]
+    pub allow_dynamic_field_loss: bool,
+    /// timeout of waiting for the new table and each of its indexes
+    #[builder(default = "Duration::from_secs(600)")]
+    pub timeout: Duration,
+}
+
+/// result of [MochowClient::rebuild_table]
+#[derive(Debug, Clone, Default)]
+pub struct RebuildTableReport {
+    pub copied_rows: u64,
+    /// row count of the new table, the same as the old one
+    pub row_count: u64,
+    pub old_table_dropped: bool,
+    /// the other aliases of the old table moved to the new one before dropping it
+    pub moved_aliases: Vec<String>,
+}
+
+// fields of the new schema also in the old one with the same type and dimension,
+// the fields only in the old schema are not copied
+fn shared_fields(old: &[FieldSchema], new: &[FieldSchema]) -> Vec<String> {
+    new.iter()
+        .filter(|field| {
+            old.iter().any(|x| {
+                x.field_name == field.field_name
+                    && x.field_type == field.field_type
+                    && x.dimension == field.dimension
+            })
+        })
+        .map(|field| field.field_name.clone())
+        .collect()
+}
+
+impl MochowClient {
+    /// rebuild the table into a new table and move the alias to it:
+    /// create the new table, copy rows by [MochowClient::select_rows] and [MochowClient::upsert_row],
+    /// rebuild its vector indexes for the copied rows by [MochowClient::rebuild_index_and_wait],
+    /// verify the row counts by [MochowClient::show_table_stats], then move the alias
+    /// by [MochowClient::move_alias] and optionally drop the old table,
+    /// writes to the old table should be stopped during the rebuild or the row counts differ,
+    /// only the fields of both schemas with the same type are copied, dynamic fields are not,
+    /// so dropping an old table with dynamic fields requires allow_dynamic_field_loss,
+    /// the new table is kept on failure for inspection,
+    /// the alias switch is not atomic, move_alias unaliases the old table before aliasing the new one,
+    /// so readers may briefly find no table by the alias, also while a failed switch is rolled back
+    pub async fn rebuild_table(
+        &self,
+        args: &RebuildTableArgs,
+    ) -> Result<RebuildTableReport, SdkError> {
+        if args.batch_size == 0 {
+            return Err(SdkError::ParamsError(
+                "batch size should be positive".to_string(),
+            ));
+        }
+        let (data_base, table, new_table) = (&args.database, &args.table, &args.new_table);
+        let old = self.desc_table(data_base, table).await?.table;
+        if args.drop_old_table && old.enable_dynamic_field && !args.allow_dynamic_field_loss {
+            return Err(SdkError::ParamsError(format!(
+                "table {} has dynamic fields which are not copied, \
+                 set allow_dynamic_field_loss to drop it",
+                table
+            )));
+        }
+        let other_aliases: Vec<String> = old
+            .aliases
+            .iter()
+            .filter(|alias| **alias != args.alias)
+            .cloned()
+            .collect();
+        let old_fields = old.schema.fields.clone();
+        let mut create_args = CreateTableArgs::from(old);
+        create_args.table = new_table.clone();
+        if let Some(schema) = &args.schema {
+            create_args.schema = schema.clone();
+        }
+        let projections = shared_fields(&old_fields, &create_args.schema.fields);
+        self.create_table_and_wait(&create_args, args.timeout)
+            .await?;
+
+        let mut report = RebuildTableReport {
+            copied_rows: self.copy_rows(args, projections).await?,
+            ..Default::default()
+        };
+
+        // the vector indexes are built on the empty table, rebuild them for the copied rows
+        for index in &create_args.schema.indexes {
+            self.wait_index_built(data_base, new_table, &index.index_name, None, args.timeout)
+                .await?;
+            if index.index_type != Some(IndexType::SECONDARY_INDEX) {
+                self.rebuild_index_and_wait(data_base, new_table, &index.index_name, args.timeout)
+                    .await?;
+            }
+        }
+
+        let old_count = self.show_table_stats(data_base, table).await?.row_count;
+        report.row_count = self.show_table_stats(data_base, new_table).await?.row_count;
+        if old_count != report.row_count {
+            return Err(SdkError::InvalidStateError(format!(
+                "table {} has {} rows while table {} has {} rows",
+                table, old_count, new_table, report.row_count
+            )));
+        }
+
+        self.move_alias(data_base, &args.alias, new_table).await?;
+        if args.drop_old_table {
+            for alias in other_aliases {
+                self.move_alias(data_base, &alias, new_table).await?;
+                report.moved_aliases.push(alias);
+            }
+            self.drop_table(data_base, table).await?;
+            report.old_table_dropped = true;
+        }
+        Ok(report)
+    }
+
+    // copy all rows of the table to the new table page by page,
+    // the shared fields are projected, vector fields are only selected by projections
+    async fn copy_rows(
+        &self,
+        args: &RebuildTableArgs,
+        projections: Vec<String>,
+    ) -> Result<u64, SdkError> {
+        let mut select_args = SelectRowsArgsBuilder::default()
+            .database(&args.database)
+            .table(&args.table)
+            .limit(args.batch_size)
+            .projections(projections)
+            .build()?;
+        let mut copied = 0;
+        loop {
+            let ret = self.select_rows::<serde_json::Value>(&select_args).await?;
+            if !ret.rows.is_empty() {
+                copied += ret.rows.len() as u64;
+                let upsert_args = UpsertRowArgsBuilder::default()
+                    .database(&args.database)
+                    .table(&args.new_table)
+                    .rows(ret.rows)
+                    .build()?;
+                self.upsert_row(&upsert_args).await?;
+            }
+            if !ret.is_truncated {
+                return Ok(copied);
+            }
+            select_args.marker = Some(ret.next_marker);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::mochow::testing::{create_table_args, field, FakeMochowServer};
+
+    fn hnsw(m: u32) -> IndexSchema {
+        IndexSchemaBuilder::default()
+            .index_name("vector_idx")
+            .field("vector")
+            .index_type(IndexType::HNSW)
+            .metric_type(MetricType::L2)
+            .params(VectorIndexParams::HNSW(HNSWIndexParam {
+                m,
+                ef_construction: 200,
+            }))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_table() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        client.create_database("book").await?;
+        let mut args = create_table_args(
+            "segments_v1",
+            vec![
+                field("id", FieldType::STRING),
+                field("page", FieldType::UINT32),
+                field("title", FieldType::STRING),
+                field("vector", FieldType::FLOAT_VECTOR),
+            ],
+            vec![hnsw(16)],
+        );
+        args.enable_dynamic_field = Some(true);
+        client.create_table(&args).await?;
+        client
+            .alias_table("book", "segments_v1", "segments")
+            .await?;
+        client.alias_table("book", "segments_v1", "latest").await?;
+        let rows: Vec<serde_json::Value> = (0..25)
+            .map(|i| {
+                serde_json::json!({
+                    "id": format!("{:02}", i),
+                    "page": i,
+                    "title": "chapter",
+                    "vector": [0.1, 0.2],
+                })
+            })
+            .collect();
+        let upsert_args = UpsertRowArgsBuilder::default()
+            .database("book")
+            .table("segments_v1")
+            .rows(rows)
+            .build()?;
+        client.upsert_row(&upsert_args).await?;
+        assert_eq!(
+            server.stale_indexes("book", "segments_v1"),
+            vec!["vector_idx"]
+        );
+
+        // title is removed and author is added
+        let schema = TableSchema {
+            fields: vec![
+                field("id", FieldType::STRING),
+                field("page", FieldType::UINT32),
+                field("author", FieldType::STRING),
+                field("vector", FieldType::FLOAT_VECTOR),
+            ],
+            indexes: vec![hnsw(32)],
+        };
+        let mut builder = RebuildTableArgsBuilder::default();
+        builder
+            .database("book")
+            .table("segments_v1")
+            .new_table("segments_v2")
+            .alias("segments")
+            .schema(schema)
+            .batch_size(10_u32)
+            .drop_old_table(true)
+            .timeout(Duration::from_secs(5));
+        assert!(matches!(
+            client.rebuild_table(&builder.build()?).await,
+            Err(SdkError::ParamsError(_))
+        ));
+        assert!(!client.has_table("book", "segments_v2").await?);
+
+        server.set_index_build_polls(2);
+        let rebuild_args = builder.allow_dynamic_field_loss(true).build()?;
+        let report = client.rebuild_table(&rebuild_args).await?;
+        assert_eq!(report.copied_rows, 25);
+        assert_eq!(report.row_count, 25);
+        assert!(report.old_table_dropped);
+        assert_eq!(report.moved_aliases, vec!["latest"]);
+        assert!(!client.has_table("book", "segments_v1").await?);
+
+        let table = client.desc_table("book", "segments_v2").await?.table;
+        assert_eq!(table.aliases, vec!["latest", "segments"]);
+        match &table.schema.indexes[0].params {
+            Some(VectorIndexParams::HNSW(params)) => assert_eq!(params.m, 32),
+            other => panic!("unexpected params {:?}", other),
+        }
+        // the index is rebuilt after the rows are copied
+        assert_eq!(table.schema.indexes[0].index_major_version, Some(2));
+        assert!(server.stale_indexes("book", "segments_v2").is_empty());
+
+        let select_args = SelectRowsArgsBuilder::default()
+            .database("book")
+            .table("segments_v2")
+            .build()?;
+        let rows = client
+            .select_rows::<serde_json::Value>(&select_args)
+            .await?
+            .rows;
+        assert_eq!(rows[0]["page"], 0);
+        assert!(rows[0].get("title").is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,332 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+declarative schema files of databases and tables in json, and yaml or toml with the `schema-files` feature
+ */
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SdkError;
+
+use super::{api::*, client::MochowClient};
+
+/// format of the schema file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaFormat {
+    Json,
+    #[cfg(feature = "schema-files")]
+    Yaml,
+    #[cfg(feature = "schema-files")]
+    Toml,
+}
+
+impl SchemaFormat {
+    /// the format by extension of the path, like .json, .yaml, .yml or .toml
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "json" => Some(SchemaFormat::Json),
+            #[cfg(feature = "schema-files")]
+            "yaml" | "yml" => Some(SchemaFormat::Yaml),
+            #[cfg(feature = "schema-files")]
+            "toml" => Some(SchemaFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// definitions of databases and their tables, field names are the same as the api,
+/// a yaml schema file looks like
+/// ```yaml
+/// databases:
+///   - database: book
+///     tables:
+///       - table: book_segments
+///         description: segments of books
+///         replication: ${REPLICATION:-3}
+///         partition:
+///           partitionType: HASH
+///           partitionNum: ${PARTITION_NUM}
+///         schema:
+///           fields:
+///             - {fieldName: id, fieldType: STRING, primaryKey: true, partitionKey: true, notNull: true}
+///             - {fieldName: vector, fieldType: FLOAT_VECTOR, notNull: true, dimension: 3}
+///           indexes:
+///             - indexName: vector_idx
+///               field: vector
+///               indexType: HNSW
+///               metricType: L2
+///               params: {M: 16, efConstruction: 200}
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaFile {
+    #[serde(default)]
+    pub databases: Vec<DatabaseDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseDefinition {
+    pub database: String,
+    #[serde(default)]
+    pub tables: Vec<TableDefinition>,
+}
+
+/// args of create table without the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableDefinition {
+    pub table: String,
+    #[serde(default)]
+    pub description: String,
+    pub replication: u32,
+    pub partition: Partition,
+    #[serde(default, rename = "enableDynamicField")]
+    pub enable_dynamic_field: bool,
+    pub schema: TableSchema,
+}
+
+impl TableDefinition {
+    pub fn to_create_table_args(&self, data_base: &str) -> Result<CreateTableArgs, SdkError> {
+        Ok(CreateTableArgsBuilder::default()
+            .database(data_base)
+            .table(&self.table)
+            .description(&self.description)
+            .replication(self.replication)
+            .partition(self.partition.clone())
+            .enable_dynamic_field(self.enable_dynamic_field)
+            .schema(self.schema.clone())
+            .build()?)
+    }
+}
+
+impl From<DescriptTable> for TableDefinition {
+    fn from(table: DescriptTable) -> Self {
+        let schema = table.schema.without_server_fields();
+        Self {
+            table: table.table,
+            description: table.description,
+            replication: table.replication,
+            partition: table.partition,
+            enable_dynamic_field: table.enable_dynamic_field,
+            schema,
+        }
+    }
+}
+
+// replace ${NAME} and ${NAME:-default} with the variables, $${ is kept as ${,
+// the values are escaped as the content of a double quoted string
+fn substitute(content: &str, vars: &HashMap<String, String>) -> Result<String, SdkError> {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| {
+            SdkError::ParamsError(format!("unclosed variable: {}", &rest[start..]))
+        })? + start;
+        let expr = &rest[start + 2..end];
+        let (name, default) = match expr.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expr, None),
+        };
+        let value = vars
+            .get(name)
+            .map(String::as_str)
+            .or(default)
+            .ok_or_else(|| SdkError::ParamsError(format!("variable {} is not set", name)))?;
+        let quoted = serde_json::Value::String(value.to_string()).to_string();
+        out.push_str(&quoted[1..quoted.len() - 1]);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn parse_error(e: impl std::fmt::Display) -> SdkError {
+    SdkError::ParamsError(format!("parse schema file failed: {}", e))
+}
+
+impl SchemaFile {
+    /// parse the content after substituting variables like `${REPLICATION}` or `${REPLICATION:-3}`,
+    /// `$${` is a literal `${`, values are escaped as the content of a double quoted string,
+    /// so string variables should be in double quotes like `"${DESCRIPTION}"`,
+    /// pass `std::env::vars().collect()` to substitute with environment variables
+    pub fn parse(
+        content: &str,
+        format: SchemaFormat,
+        vars: &HashMap<String, String>,
+    ) -> Result<Self, SdkError> {
+        let content = substitute(content, vars)?;
+        match format {
+            SchemaFormat::Json => serde_json::from_str(&content).map_err(parse_error),
+            #[cfg(feature = "schema-files")]
+            SchemaFormat::Yaml => serde_yaml::from_str(&content).map_err(parse_error),
+            #[cfg(feature = "schema-files")]
+            SchemaFormat::Toml => toml::from_str(&content).map_err(parse_error),
+        }
+    }
+
+    /// load the file, the format is decided by the extension
+    pub fn load(path: impl AsRef<Path>, vars: &HashMap<String, String>) -> Result<Self, SdkError> {
+        let path = path.as_ref();
+        let format = SchemaFormat::from_path(path).ok_or_else(|| {
+            SdkError::ParamsError(format!("unknown schema file format: {}", path.display()))
+        })?;
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            SdkError::ParamsError(format!("read schema file {} failed: {}", path.display(), e))
+        })?;
+        Self::parse(&content, format, vars)
+    }
+
+    /// dump the definitions in the format, `${` in values is escaped as `$${` to be parsed back
+    pub fn dump(&self, format: SchemaFormat) -> Result<String, SdkError> {
+        let dump_error = |e: &dyn std::fmt::Display| {
+            SdkError::ParamsError(format!("dump schema file failed: {}", e))
+        };
+        let content = match format {
+            SchemaFormat::Json => serde_json::to_string_pretty(self).map_err(|e| dump_error(&e)),
+            #[cfg(feature = "schema-files")]
+            SchemaFormat::Yaml => serde_yaml::to_string(self).map_err(|e| dump_error(&e)),
+            #[cfg(feature = "schema-files")]
+            SchemaFormat::Toml => toml::to_string_pretty(self).map_err(|e| dump_error(&e)),
+        }?;
+        Ok(content.replace("${", "$${"))
+    }
+
+    /// save the file, the format is decided by the extension
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SdkError> {
+        let path = path.as_ref();
+        let format = SchemaFormat::from_path(path).ok_or_else(|| {
+            SdkError::ParamsError(format!("unknown schema file format: {}", path.display()))
+        })?;
+        std::fs::write(path, self.dump(format)?).map_err(|e| {
+            SdkError::ParamsError(format!(
+                "write schema file {} failed: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// create table args of all tables in the file
+    pub fn create_table_args(&self) -> Result<Vec<CreateTableArgs>, SdkError> {
+        let mut args = vec![];
+        for db in &self.databases {
+            for table in &db.tables {
+                args.push(table.to_create_table_args(&db.database)?);
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl MochowClient {
+    /// dump the definitions of the databases and all of their tables by desc_table,
+    /// see [SchemaFile::save]
+    pub async fn dump_schema(&self, data_bases: &[&str]) -> Result<SchemaFile, SdkError> {
+        let mut file = SchemaFile::default();
+        for data_base in data_bases {
+            let mut tables = vec![];
+            for table in self.list_table(data_base).await?.tables {
+                let table = self.desc_table(data_base, &table).await?.table;
+                tables.push(TableDefinition::from(table));
+            }
+            file.databases.push(DatabaseDefinition {
+                database: data_base.to_string(),
+                tables,
+            });
+        }
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::mochow::testing::FakeMochowServer;
+
+    const SCHEMA: &str = r#"{
+        "databases": [{
+            "database": "book",
+            "tables": [{
+                "table": "segments",
+                "description": "${DESCRIPTION}, $${NOT_A_VARIABLE}",
+                "replication": ${REPLICATION:-3},
+                "partition": {"partitionType": "HASH", "partitionNum": ${PARTITION_NUM}},
+                "schema": {
+                    "fields": [
+                        {"fieldName": "id", "fieldType": "STRING", "primaryKey": true, "partitionKey": true, "notNull": true},
+                        {"fieldName": "vector", "fieldType": "FLOAT_VECTOR", "notNull": true, "dimension": 3}
+                    ],
+                    "indexes": [{
+                        "indexName": "vector_idx", "field": "vector", "indexType": "HNSW", "metricType": "L2",
+                        "params": {"M": 16, "efConstruction": 200}
+                    }]
+                }
+            }]
+        }]
+    }"#;
+
+    #[tokio::test]
+    async fn test_schema_file() -> Result<()> {
+        assert!(SchemaFile::parse(SCHEMA, SchemaFormat::Json, &HashMap::new()).is_err());
+        let vars = HashMap::from([
+            ("PARTITION_NUM".to_string(), "2".to_string()),
+            ("DESCRIPTION".to_string(), r#"costs "${PRICE}""#.to_string()),
+        ]);
+        let file = SchemaFile::parse(SCHEMA, SchemaFormat::Json, &vars)?;
+        let args = file.create_table_args()?;
+        assert_eq!(args.len(), 1);
+        assert_eq!(
+            args[0].description.as_deref(),
+            Some(r#"costs "${PRICE}", ${NOT_A_VARIABLE}"#)
+        );
+        assert_eq!(args[0].replication, 3);
+        assert_eq!(args[0].partition.partition_num, 2);
+
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        client.create_database("book").await?;
+        client.create_table(&args[0]).await?;
+        let dumped = client.dump_schema(&["book"]).await?;
+        let formats = [
+            SchemaFormat::Json,
+            #[cfg(feature = "schema-files")]
+            SchemaFormat::Yaml,
+            #[cfg(feature = "schema-files")]
+            SchemaFormat::Toml,
+        ];
+        for format in formats {
+            let content = dumped.dump(format)?;
+            assert!(!content.contains("indexMajorVersion"));
+            let loaded = SchemaFile::parse(&content, format, &HashMap::new())?;
+            assert_eq!(
+                Some(&loaded.databases[0].tables[0].description),
+                args[0].description.as_ref()
+            );
+            assert_eq!(
+                serde_json::to_value(&loaded)?,
+                serde_json::to_value(&dumped)?
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1028 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+in-memory implementation of the mochow http api, requests and responses are json values
+with the same layout as the http bodies
+ */
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::mochow::api::{
+    FieldSchema, FieldType, IndexSchema, IndexType, MetricType, ServerErrorCode,
+};
+
+use super::filter::Filter;
+
+/// error returned by the fake server, with the server error code of mochow
+#[derive(Debug, Clone)]
+pub struct FakeError {
+    pub code: ServerErrorCode,
+    pub msg: String,
+}
+
+impl FakeError {
+    pub(crate) fn new(code: ServerErrorCode, msg: impl Into<String>) -> Self {
+        Self {
+            code,
+            msg: msg.into(),
+        }
+    }
+
+    /// http status code of the error response
+    pub fn status_code(&self) -> u16 {
+        match self.code {
+            ServerErrorCode::AUTHENTICATION_FAILED => 401,
+            ServerErrorCode::PERMISSION_DENIED => 403,
+            ServerErrorCode::INVALID_HTTP_URL
+            | ServerErrorCode::DB_NOT_EXIST
+            | ServerErrorCode::TABLE_NOT_EXIST
+            | ServerErrorCode::ALIAS_NOT_EXIST
+            | ServerErrorCode::FIELD_NOT_EXIST
+            | ServerErrorCode::VECTOR_FIELD_NOT_EXIST
+            | ServerErrorCode::INDEX_NOT_EXIST
+            | ServerErrorCode::ROW_KEY_NOT_FOUND => 404,
+            ServerErrorCode::DB_ALREADY_EXIST
+            | ServerErrorCode::TABLE_ALREADY_EXIST
+            | ServerErrorCode::ALIAS_ALREADY_EXIST
+            | ServerErrorCode::FIELD_ALREADY_EXIST
+            | ServerErrorCode::INDEX_ALREADY_EXIST
+            | ServerErrorCode::PRIMARY_KEY_DUPLICATED
+            | ServerErrorCode::DB_NOT_EMPTY => 409,
+            ServerErrorCode::INTERNAL_ERROR | ServerErrorCode::UNKNOWN => 500,
+            _ => 400,
+        }
+    }
+
+    /// json body of the error response
+    pub fn to_json(&self) -> Value {
+        json!({"code": self.code.clone() as i32, "msg": self.msg})
+    }
+}
+
+type FakeResult = Result<Value, FakeError>;
+
+/// ordered key of a row, built from the primary key value
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum RowKey {
+    Int(i128),
+    Str(String),
+    Other(String),
+}
+
+impl RowKey {
+    fn from_value(v: &Value) -> Self {
+        match v {
+            Value::Number(n) if n.is_i64() => RowKey::Int(n.as_i64().unwrap() as i128),
+            Value::Number(n) if n.is_u64() => RowKey::Int(n.as_u64().unwrap() as i128),
+            Value::String(s) => RowKey::Str(s.clone()),
+            other => RowKey::Other(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FakeIndex {
+    schema: IndexSchema,
+    major_version: u64,
+    /// polls of desc_index left reporting BUILDING, the major version is increased after them
+    building_polls: u64,
+    /// rows are written since the vector index was built, it's not rebuilt automatically
+    stale: bool,
+}
+
+impl FakeIndex {
+    fn new(schema: IndexSchema, build_polls: u64) -> Self {
+        let mut index = Self {
+            schema,
+            major_version: 0,
+            building_polls: 0,
+            stale: false,
+        };
+        index.start_build(build_polls);
+        index
+    }
+
+    fn start_build(&mut self, build_polls: u64) {
+        if build_polls == 0 {
+            self.major_version += 1;
+        }
+        self.building_polls = build_polls;
+        self.stale = false;
+    }
+
+    fn poll(&mut self) {
+        if self.building_polls > 0 {
+            self.building_polls -= 1;
+            if self.building_polls == 0 {
+                self.major_version += 1;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FakeTable {
+    create_time: String,
+    description: String,
+    replication: Value,
+    partition: Value,
+    enable_dynamic_field: bool,
+    fields: Vec<FieldSchema>,
+    indexes: Vec<FakeIndex>,
+    /// deleted indexes still described by desc_index, with the polls left
+    deleting_indexes: Vec<(FakeIndex, u64)>,
+    rows: BTreeMap<RowKey, Map<String, Value>>,
+    next_auto_id: u64,
+}
+
+impl FakeTable {
+    // written rows are not in the vector indexes without auto build until they are rebuilt
+    fn mark_stale(&mut self) {
+        for index in &mut self.indexes {
+            if index.schema.index_type != Some(IndexType::SECONDARY_INDEX)
+                && !index.schema.auto_build
+            {
+                index.stale = true;
+            }
+        }
+    }
+
+    fn primary_key(&self) -> Option<&FieldSchema> {
+        self.fields.iter().find(|f| f.primary_key)
+    }
+
+    fn is_vector_field(&self, name: &str) -> bool {
+        self.fields
+            .iter()
+            .any(|f| f.field_name == name && matches!(f.field_type, FieldType::FLOAT_VECTOR))
+    }
+
+    fn row_key_of(&self, primary_key: &Value) -> Result<RowKey, FakeError> {
+        let pk = self.primary_key().ok_or_else(|| {
+            FakeError::new(
+                ServerErrorCode::INVALID_TABLE_SCHEMA,
+                "table has no primary key",
+            )
+        })?;
+        let value = match primary_key {
+            Value::Object(map) => map.get(&pk.field_name).cloned(),
+            Value::Null => None,
+            other => Some(other.clone()),
+        };
+        match value {
+            Some(v) if !v.is_null() => Ok(RowKey::from_value(&v)),
+            _ => Err(FakeError::new(
+                ServerErrorCode::INVALID_PARAMETER,
+                format!("primary key {} is missing", pk.field_name),
+            )),
+        }
+    }
+
+    // check the primary key, not null fields and dimension of vector fields like the server
+    fn validate_row(&self, row: &Map<String, Value>) -> Result<(), FakeError> {
+        for field in &self.fields {
+            let value = match row.get(&field.field_name) {
+                Some(v) if !v.is_null() => v,
+                _ if field.primary_key || field.not_null => {
+                    return Err(FakeError::new(
+                        ServerErrorCode::INVALID_PARAMETER,
+                        format!("field {} can not be null", field.field_name),
+                    ))
+                }
+                _ => continue,
+            };
+            if !matches!(field.field_type, FieldType::FLOAT_VECTOR) {
+                continue;
+            }
+            let dimension = vector_of(Some(value)).map(|v| v.len()).ok_or_else(|| {
+                FakeError::new(
+                    ServerErrorCode::INVALID_PARAMETER,
+                    format!("field {} should be an array of float", field.field_name),
+                )
+            })?;
+            match field.dimension {
+                Some(expected) if expected as usize != dimension => {
+                    return Err(FakeError::new(
+                        ServerErrorCode::INVALID_PARAMETER,
+                        format!(
+                            "dimension of field {} should be {}, but got {}",
+                            field.field_name, expected, dimension
+                        ),
+                    ))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // projections and retrieveVector of the output row
+    fn project(
+        &self,
+        row: &Map<String, Value>,
+        projections: Option<&Vec<Value>>,
+        retrieve_vector: bool,
+    ) -> Value {
+        let mut out = Map::new();
+        for (k, v) in row {
+            let projected = match projections {
+                Some(p) if !p.is_empty() => p.iter().any(|name| name.as_str() == Some(k)),
+                _ => true,
+            };
+            if projected && (retrieve_vector || !self.is_vector_field(k)) {
+                out.insert(k.clone(), v.clone());
+            }
+        }
+        Value::Object(out)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct FakeDatabase {
+    tables: BTreeMap<String, FakeTable>,
+    /// alias -> table
+    aliases: BTreeMap<String, String>,
+}
+
+/// the state of a fake mochow instance
+#[derive(Debug, Clone, Default)]
+pub struct FakeMochow {
+    databases: BTreeMap<String, FakeDatabase>,
+    index_build_polls: u64,
+    index_delete_polls: u64,
+}
+
+fn str_arg<'a>(body: &'a Value, key: &str) -> Result<&'a str, FakeError> {
+    body.get(key).and_then(Value::as_str).ok_or_else(|| {
+        FakeError::new(
+            ServerErrorCode::INVALID_PARAMETER,
+            format!("parameter {} is required", key),
+        )
+    })
+}
+
+fn parse_arg<T: serde::de::DeserializeOwned>(value: &Value, key: &str) -> Result<T, FakeError> {
+    serde_json::from_value(value.clone()).map_err(|e| {
+        FakeError::new(
+            ServerErrorCode::INVALID_PARAMETER,
+            format!("invalid parameter {}: {}", key, e),
+        )
+    })
+}
+
+fn success(mut extra: Value) -> FakeResult {
+    let obj = extra.as_object_mut().unwrap();
+    obj.insert("code".to_string(), json!(0));
+    obj.insert("msg".to_string(), json!("Success"));
+    Ok(extra)
+}
+
+fn now_string() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default() as i64;
+    let (days, rem) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    // civil from days, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        y,
+        m,
+        d,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+fn vector_of(value: Option<&Value>) -> Option<Vec<f64>> {
+    value?.as_array()?.iter().map(Value::as_f64).collect()
+}
+
+/// distance and score of two vectors, the higher the score, the more similar
+fn distance(metric: &MetricType, a: &[f64], b: &[f64]) -> (f64, f64) {
+    match metric {
+        MetricType::L2 => {
+            let d: f64 = a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum();
+            (d, 1.0 / (1.0 + d))
+        }
+        MetricType::IP => {
+            let d: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+            (d, d)
+        }
+        MetricType::COSINE => {
+            let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+            let na = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+            let nb = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+            let d = if na == 0.0 || nb == 0.0 {
+                0.0
+            } else {
+                dot / (na * nb)
+            };
+            (d, d)
+        }
+    }
+}
+
+impl FakeMochow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// number of desc_index polls an index reports BUILDING after it's created or rebuilt,
+    /// default is 0, that's built immediately
+    pub fn set_index_build_polls(&mut self, polls: u64) {
+        self.index_build_polls = polls;
+    }
+
+    /// vector indexes of the table not rebuilt since rows are written,
+    /// empty if the table does not exist
+    pub fn stale_indexes(&self, database: &str, table: &str) -> Vec<String> {
+        self.table(&json!({ "database": database, "table": table }))
+            .map(|table| {
+                table
+                    .indexes
+                    .iter()
+                    .filter(|index| index.stale)
+                    .map(|index| index.schema.index_name.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// number of desc_index polls a deleted index is still described, default is 0,
+    /// it can't be created again before that
+    pub fn set_index_delete_polls(&mut self, polls: u64) {
+        self.index_delete_polls = polls;
+    }
+
+    /// handle a request, `path` is like /v1/table and `query` is the action like create
+    pub fn handle(&mut self, method: &str, path: &str, query: &str, body: Value) -> FakeResult {
+        let resource = path.trim_start_matches("/v1/");
+        let action = query.split('&').next().unwrap_or_default();
+        match (method, resource, action) {
+            ("POST", "database", "create") => self.create_database(&body),
+            ("DELETE", "database", _) => self.drop_database(&body),
+            ("POST", "database", "list") => self.list_database(),
+            ("POST", "table", "create") => self.create_table(&body),
+            ("DELETE", "table", _) => self.drop_table(&body),
+            ("POST", "table", "list") => self.list_table(&body),
+            ("POST", "table", "desc") => self.desc_table(&body),
+            ("POST", "table", "addField") => self.add_field(&body),
+            ("POST", "table", "stats") => self.stats_table(&body),
+            ("POST", "table", "alias") => self.alias_table(&body),
+            ("POST", "table", "unalias") => self.unalias_table(&body),
+            ("POST", "index", "create") => self.create_index(&body),
+            ("POST", "index", "desc") => self.desc_index(&body),
+            ("POST", "index", "rebuild") => self.rebuild_index(&body),
+            ("POST", "index", "modify") => self.modify_index(&body),
+            ("DELETE", "index", _) => self.delete_index(&body),
+            ("POST", "row", "insert") => self.write_rows(&body, false),
+            ("POST", "row", "upsert") => self.write_rows(&body, true),
+            ("POST", "row", "update") => self.update_row(&body),
+            ("POST", "row", "delete") => self.delete_rows(&body),
+            ("POST", "row", "query") => self.query_row(&body),
+            ("POST", "row", "search") => self.search_rows(&body),
+            ("POST", "row", "select") => self.select_rows(&body),
+            ("POST", "row", "batchSearch") => self.batch_search_rows(&body),
+            _ => Err(FakeError::new(
+                ServerErrorCode::INVALID_HTTP_URL,
+                format!("unsupported request {} {}?{}", method, path, query),
+            )),
+        }
+    }
+
+    fn database(&self, body: &Value) -> Result<&FakeDatabase, FakeError> {
+        let name = str_arg(body, "database")?;
+        self.databases.get(name).ok_or_else(|| {
+            FakeError::new(
+                ServerErrorCode::DB_NOT_EXIST,
+                format!("database {} not exist", name),
+            )
+        })
+    }
+
+    fn database_mut(&mut self, body: &Value) -> Result<&mut FakeDatabase, FakeError> {
+        let name = str_arg(body, "database")?;
+        self.databases.get_mut(name).ok_or_else(|| {
+            FakeError::new(
+                ServerErrorCode::DB_NOT_EXIST,
+                format!("database {} not exist", name),
+            )
+        })
+    }
+
+    // table name in the request, aliases are resolved for data operations
+    fn table_name(&self, body: &Value, resolve_alias: bool) -> Result<String, FakeError> {
+        let db = self.database(body)?;
+        let name = str_arg(body, "table")?;
+        if db.tables.contains_key(name) {
+            return Ok(name.to_string());
+        }
+        match db.aliases.get(name) {
+            Some(table) if resolve_alias => Ok(table.clone()),
+            _ => Err(FakeError::new(
+                ServerErrorCode::TABLE_NOT_EXIST,
+                format!("table {} not exist", name),
+            )),
+        }
+    }
+
+    fn table(&self, body: &Value) -> Result<&FakeTable, FakeError> {
+        let name = self.table_name(body, true)?;
+        Ok(&self.database(body)?.tables[&name])
+    }
+
+    fn table_mut(&mut self, body: &Value) -> Result<&mut FakeTable, FakeError> {
+        let name = self.table_name(body, true)?;
+        Ok(self.database_mut(body)?.tables.get_mut(&name).unwrap())
+    }
+
+    fn create_database(&mut self, body: &Value) -> FakeResult {
+        let name = str_arg(body, "database")?;
+        if self.databases.contains_key(name) {
+            return Err(FakeError::new(
+                ServerErrorCode::DB_ALREADY_EXIST,
+                format!("database {} already exist", name),
+            ));
+        }
+        self.databases
+            .insert(name.to_string(), FakeDatabase::default());
+        success(json!({}))
+    }
+
+    fn drop_database(&mut self, body: &Value) -> FakeResult {
+        let name = str_arg(body, "database")?.to_string();
+        if !self.database(body)?.tables.is_empty() {
+            return Err(FakeError::new(
+                ServerErrorCode::DB_NOT_EMPTY,
+                format!("database {} is not empty", name),
+            ));
+        }
+        self.databases.remove(&name);
+        success(json!({}))
+    }
+
+    fn list_database(&self) -> FakeResult {
+        let names: Vec<&String> = self.databases.keys().collect();
+        success(json!({ "databases": names }))
+    }
+
+    fn create_table(&mut self, body: &Value) -> FakeResult {
+        let build_polls = self.index_build_polls;
+        let name = str_arg(body, "table")?.to_string();
+        let schema = body.get("schema").cloned().unwrap_or(json!({}));
+        let fields: Vec<FieldSchema> =
+            parse_arg(schema.get("fields").unwrap_or(&json!([])), "fields")?;
+        let indexes: Vec<IndexSchema> =
+            parse_arg(schema.get("indexes").unwrap_or(&json!([])), "indexes")?;
+        let db = self.database_mut(body)?;
+        if db.tables.contains_key(&name) || db.aliases.contains_key(&name) {
+            return Err(FakeError::new(
+                ServerErrorCode::TABLE_ALREADY_EXIST,
+                format!("table {} already exist", name),
+            ));
+        }
+        if fields.iter().filter(|f| f.primary_key).count() != 1 {
+            return Err(FakeError::new(
+                ServerErrorCode::INVALID_TABLE_SCHEMA,
+                "table must have exactly one primary key",
+            ));
+        }
+        for index in &indexes {
+            if !fields.iter().any(|f| f.field_name == index.field) {
+                return Err(FakeError::new(
+                    ServerErrorCode::FIELD_NOT_EXIST,
+                    format!(
+                        "field {} of index {} not exist",
+                        index.field, index.index_name
+                    ),
+                ));
+            }
+        }
+        let table = FakeTable {
+            create_time: now_string(),
+            description: body
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            replication: body.get("replication").cloned().unwrap_or(json!(1)),
+            partition: body
+                .get("partition")
+                .cloned()
+                .unwrap_or(json!({"partitionType": "HASH", "partitionNum": 1})),
+            enable_dynamic_field: body
+                .get("enableDynamicField")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            fields,
+            indexes: indexes
+                .into_iter()
+                .map(|schema| FakeIndex::new(schema, build_polls))
+                .collect(),
+            deleting_indexes: vec![],
+            rows: BTreeMap::new(),
+            next_auto_id: 1,
+        };
+        db.tables.insert(name, table);
+        success(json!({}))
+    }
+
+    fn drop_table(&mut self, body: &Value) -> FakeResult {
+        let name = self.table_name(body, false)?;
+        let db = self.database_mut(body)?;
+        db.tables.remove(&name);
+        db.aliases.retain(|_, table| *table != name);
+        success(json!({}))
+    }
+
+    fn list_table(&self, body: &Value) -> FakeResult {
+        let names: Vec<&String> = self.database(body)?.tables.keys().collect();
+        success(json!({ "tables": names }))
+    }
+
+    fn index_json(index: &FakeIndex) -> Value {
+        let mut value = serde_json::to_value(&index.schema).unwrap_or(json!({}));
+        let obj = value.as_object_mut().unwrap();
+        let state = if index.building_polls > 0 {
+            "BUILDING"
+        } else {
+            "NORMAL"
+        };
+        obj.insert("state".to_string(), json!(state));
+        obj.insert("indexMajorVersion".to_string(), json!(index.major_version));
+        value
+    }
+
+    fn desc_table(&self, body: &Value) -> FakeResult {
+        let name = self.table_name(body, false)?;
+        let db = self.database(body)?;
+        let table = &db.tables[&name];
+        let aliases: Vec<&String> = db
+            .aliases
+            .iter()
+            .filter(|(_, t)| **t == name)
+            .map(|(alias, _)| alias)
+            .collect();
+        let indexes: Vec<Value> = table.indexes.iter().map(Self::index_json).collect();
+        success(json!({
+            "table": {
+                "database": str_arg(body, "database")?,
+                "table": name,
+                "createTime": table.create_time,
+                "description": table.description,
+                "replication": table.replication,
+                "partition": table.partition,
+                "enableDynamicField": table.enable_dynamic_field,
+                "state": "NORMAL",
+                "aliases": aliases,
+                "schema": {
+                    "fields": table.fields,
+                    "indexes": indexes,
+                },
+            }
+        }))
+    }
+
+    fn add_field(&mut self, body: &Value) -> FakeResult {
+        let fields: Vec<FieldSchema> = parse_arg(
+            body.pointer("/schema/fields").unwrap_or(&json!([])),
+            "fields",
+        )?;
+        let table = self.table_mut(body)?;
+        for field in fields {
+            if table
+                .fields
+                .iter()
+                .any(|f| f.field_name == field.field_name)
+            {
+                return Err(FakeError::new(
+                    ServerErrorCode::FIELD_ALREADY_EXIST,
+                    format!("field {} already exist", field.field_name),
+                ));
+            }
+            table.fields.push(field);
+        }
+        success(json!({}))
+    }
+
+    fn stats_table(&self, body: &Value) -> FakeResult {
+        let table = self.table(body)?;
+        let memory: usize = table
+            .rows
+            .values()
+            .map(|row| Value::Object(row.clone()).to_string().len())
+            .sum();
+        success(json!({
+            "rowCount": table.rows.len(),
+            "memorySizeInByte": memory,
+            "diskSizeInByte": 0,
+        }))
+    }
+
+    fn alias_table(&mut self, body: &Value) -> FakeResult {
+        let name = self.table_name(body, false)?;
+        let alias = str_arg(body, "alias")?.to_string();
+        let db = self.database_mut(body)?;
+        if db.aliases.contains_key(&alias) || db.tables.contains_key(&alias) {
+            return Err(FakeError::new(
+                ServerErrorCode::ALIAS_ALREADY_EXIST,
+                format!("alias {} already exist", alias),
+            ));
+        }
+        db.aliases.insert(alias, name);
+        success(json!({}))
+    }
+
+    fn unalias_table(&mut self, body: &Value) -> FakeResult {
+        let name = self.table_name(body, false)?;
+        let alias = str_arg(body, "alias")?.to_string();
+        let db = self.database_mut(body)?;
+        if db.aliases.get(&alias) != Some(&name) {
+            return Err(FakeError::new(
+                ServerErrorCode::ALIAS_NOT_EXIST,
+                format!("alias {} of table {} not exist", alias, name),
+            ));
+        }
+        db.aliases.remove(&alias);
+        success(json!({}))
+    }
+
+    fn index_position(table: &FakeTable, body: &Value) -> Result<usize, FakeError> {
+        let name = str_arg(body, "indexName")?;
+        table
+            .indexes
+            .iter()
+            .position(|i| i.schema.index_name == name)
+            .ok_or_else(|| {
+                FakeError::new(
+                    ServerErrorCode::INDEX_NOT_EXIST,
+                    format!("index {} not exist", name),
+                )
+            })
+    }
+
+    fn create_index(&mut self, body: &Value) -> FakeResult {
+        let build_polls = self.index_build_polls;
+        let indexes: Vec<IndexSchema> =
+            parse_arg(body.get("indexes").unwrap_or(&json!([])), "indexes")?;
+        let table = self.table_mut(body)?;
+        for index in indexes {
+            if table
+                .indexes
+                .iter()
+                .chain(table.deleting_indexes.iter().map(|(i, _)| i))
+                .any(|i| i.schema.index_name == index.index_name)
+            {
+                return Err(FakeError::new(
+                    ServerErrorCode::INDEX_ALREADY_EXIST,
+                    format!("index {} already exist", index.index_name),
+                ));
+            }
+            if !table.fields.iter().any(|f| f.field_name == index.field) {
+                return Err(FakeError::new(
+                    ServerErrorCode::FIELD_NOT_EXIST,
+                    format!("field {} not exist", index.field),
+                ));
+            }
+            table.indexes.push(FakeIndex::new(index, build_polls));
+        }
+        success(json!({}))
+    }
+
+    fn desc_index(&mut self, body: &Value) -> FakeResult {
+        let table = self.table_mut(body)?;
+        let name = str_arg(body, "indexName")?;
+        if let Some(pos) = table
+            .deleting_indexes
+            .iter()
+            .position(|(i, _)| i.schema.index_name == name)
+        {
+            let (index, polls) = &mut table.deleting_indexes[pos];
+            let index = Self::index_json(index);
+            *polls -= 1;
+            if *polls == 0 {
+                table.deleting_indexes.remove(pos);
+            }
+            return success(json!({ "index": index }));
+        }
+        let pos = Self::index_position(table, body)?;
+        let index = Self::index_json(&table.indexes[pos]);
+        table.indexes[pos].poll();
+        success(json!({ "index": index }))
+    }
+
+    fn rebuild_index(&mut self, body: &Value) -> FakeResult {
+        let build_polls = self.index_build_polls;
+        let table = self.table_mut(body)?;
+        let pos = Self::index_position(table, body)?;
+        table.indexes[pos].start_build(build_polls);
+        success(json!({}))
+    }
+
+    fn modify_index(&mut self, body: &Value) -> FakeResult {
+        let index = body.get("index").cloned().unwrap_or(json!({}));
+        let table = self.table_mut(body)?;
+        let pos = Self::index_position(table, &index)?;
+        let schema = &mut table.indexes[pos].schema;
+        if let Some(auto_build) = index.get("autoBuild").and_then(Value::as_bool) {
+            schema.auto_build = auto_build;
+        }
+        if let Some(policy) = index.get("autoBuildPolicy") {
+            schema.auto_build_policy = Some(parse_arg(policy, "autoBuildPolicy")?);
+        }
+        success(json!({}))
+    }
+
+    fn delete_index(&mut self, body: &Value) -> FakeResult {
+        let delete_polls = self.index_delete_polls;
+        let table = self.table_mut(body)?;
+        let pos = Self::index_position(table, body)?;
+        let index = table.indexes.remove(pos);
+        if delete_polls > 0 {
+            table.deleting_indexes.push((index, delete_polls));
+        }
+        success(json!({}))
+    }
+
+    fn write_rows(&mut self, body: &Value, upsert: bool) -> FakeResult {
+        let rows = body
+            .get("rows")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let table = self.table_mut(body)?;
+        let pk = table.primary_key().cloned().ok_or_else(|| {
+            FakeError::new(
+                ServerErrorCode::INVALID_TABLE_SCHEMA,
+                "table has no primary key",
+            )
+        })?;
+        let mut written = BTreeMap::new();
+        for row in rows {
+            let mut row = match row {
+                Value::Object(row) => row,
+                _ => {
+                    return Err(FakeError::new(
+                        ServerErrorCode::INVALID_PARAMETER,
+                        "row should be a json object",
+                    ))
+                }
+            };
+            if pk.auto_increment && matches!(row.get(&pk.field_name), None | Some(Value::Null)) {
+                row.insert(pk.field_name.clone(), json!(table.next_auto_id));
+                table.next_auto_id += 1;
+            }
+            table.validate_row(&row)?;
+            let key = table.row_key_of(&Value::Object(row.clone()))?;
+            if !upsert && (table.rows.contains_key(&key) || written.contains_key(&key)) {
+                return Err(FakeError::new(
+                    ServerErrorCode::PRIMARY_KEY_DUPLICATED,
+                    format!("primary key {:?} duplicated", key),
+                ));
+            }
+            written.insert(key, row);
+        }
+        // the rows are written only if all of them are valid
+        let affected = written.len();
+        table.rows.extend(written);
+        table.mark_stale();
+        success(json!({ "affectedCount": affected }))
+    }
+
+    fn update_row(&mut self, body: &Value) -> FakeResult {
+        let table = self.table_mut(body)?;
+        let key = table.row_key_of(body.get("primaryKey").unwrap_or(&Value::Null))?;
+        let pk_name = table.primary_key().unwrap().field_name.clone();
+        let update = body
+            .get("update")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        if update.contains_key(&pk_name) {
+            return Err(FakeError::new(
+                ServerErrorCode::INVALID_PARAMETER,
+                "updating primary key is not allowed",
+            ));
+        }
+        let mut row =
+            table.rows.get(&key).cloned().ok_or_else(|| {
+                FakeError::new(ServerErrorCode::ROW_KEY_NOT_FOUND, "row not found")
+            })?;
+        row.extend(update);
+        table.validate_row(&row)?;
+        table.rows.insert(key, row);
+        table.mark_stale();
+        success(json!({}))
+    }
+
+    fn delete_rows(&mut self, body: &Value) -> FakeResult {
+        let filter = match body.get("filter").and_then(Value::as_str) {
+            Some(expr) => Some(
+                Filter::parse(expr)
+                    .map_err(|e| FakeError::new(ServerErrorCode::INVALID_PARAMETER, e))?,
+            ),
+            None => None,
+        };
+        let table = self.table_mut(body)?;
+        if let Some(primary_key) = body.get("primaryKey") {
+            let key = table.row_key_of(primary_key)?;
+            table.rows.remove(&key);
+        } else if let Some(filter) = filter {
+            table.rows.retain(|_, row| !filter.matches(row));
+        } else {
+            return Err(FakeError::new(
+                ServerErrorCode::INVALID_PARAMETER,
+                "primaryKey or filter is required",
+            ));
+        }
+        table.mark_stale();
+        success(json!({}))
+    }
+
+    fn query_row(&self, body: &Value) -> FakeResult {
+        let table = self.table(body)?;
+        let key = table.row_key_of(body.get("primaryKey").unwrap_or(&Value::Null))?;
+        let row = table
+            .rows
+            .get(&key)
+            .ok_or_else(|| FakeError::new(ServerErrorCode::ROW_KEY_NOT_FOUND, "row not found"))?;
+        let retrieve_vector = body
+            .get("retrieveVector")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let projections = body.get("projections").and_then(Value::as_array);
+        success(json!({ "row": table.project(row, projections, retrieve_vector) }))
+    }
+
+    // brute force search of the target vectors, returns the result rows of every vector
+    fn search(
+        &self,
+        body: &Value,
+        anns: &Value,
+        vectors: Vec<Vec<f64>>,
+    ) -> Result<Vec<Value>, FakeError> {
+        let table = self.table(body)?;
+        let field = str_arg(anns, "vectorField")?;
+        if !table.is_vector_field(field) {
+            return Err(FakeError::new(
+                ServerErrorCode::VECTOR_FIELD_NOT_EXIST,
+                format!("vector field {} not exist", field),
+            ));
+        }
+        let metric = table
+            .indexes
+            .iter()
+            .find(|i| i.schema.field == field)
+            .and_then(|i| i.schema.metric_type.clone())
+            .unwrap_or(MetricType::L2);
+        let filter = match anns.get("filter").and_then(Value::as_str) {
+            Some(expr) => Some(
+                Filter::parse(expr)
+                    .map_err(|e| FakeError::new(ServerErrorCode::INVALID_PARAMETER, e))?,
+            ),
+            None => None,
+        };
+        let params = anns.get("params").cloned().unwrap_or(json!({}));
+        let limit = params.get("limit").and_then(Value::as_u64).unwrap_or(50) as usize;
+        let near = params.get("distanceNear").and_then(Value::as_f64);
+        let far = params.get("distanceFar").and_then(Value::as_f64);
+        let retrieve_vector = body
+            .get("retrieveVector")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let projections = body.get("projections").and_then(Value::as_array);
+
+        let mut results = Vec::new();
+        for target in vectors {
+            let mut hits: Vec<(f64, f64, &Map<String, Value>)> = table
+                .rows
+                .values()
+                .filter(|row| filter.iter().all(|f| f.matches(row)))
+                .filter_map(|row| {
+                    let v = vector_of(row.get(field))?;
+                    let (d, s) = distance(&metric, &target, &v);
+                    Some((d, s, row))
+                })
+                .filter(|(d, _, _)| match (near, far) {
+                    (Some(a), Some(b)) => *d >= a.min(b) && *d <= a.max(b),
+                    (Some(a), None) | (None, Some(a)) => match metric {
+                        MetricType::L2 => *d <= a,
+                        _ => *d >= a,
+                    },
+                    (None, None) => true,
+                })
+                .collect();
+            hits.sort_by(|a, b| b.1.total_cmp(&a.1));
+            hits.truncate(limit);
+            let rows: Vec<Value> = hits
+                .into_iter()
+                .map(|(d, s, row)| {
+                    json!({
+                        "row": table.project(row, projections, retrieve_vector),
+                        "distance": d,
+                        "score": s,
+                    })
+                })
+                .collect();
+            results.push(json!({ "searchVectorFloats": target, "rows": rows }));
+        }
+        Ok(results)
+    }
+
+    fn search_rows(&self, body: &Value) -> FakeResult {
+        let anns = body.get("anns").cloned().unwrap_or(json!({}));
+        let vector = vector_of(anns.get("vectorFloats")).ok_or_else(|| {
+            FakeError::new(
+                ServerErrorCode::INVALID_PARAMETER,
+                "vectorFloats is required",
+            )
+        })?;
+        let mut results = self.search(body, &anns, vec![vector])?;
+        success(json!({ "rows": results.remove(0)["rows"].take() }))
+    }
+
+    fn batch_search_rows(&self, body: &Value) -> FakeResult {
+        let anns = body.get("anns").cloned().unwrap_or(json!({}));
+        let vectors: Option<Vec<Vec<f64>>> = anns
+            .get("vectorFloats")
+            .and_then(Value::as_array)
+            .and_then(|vs| vs.iter().map(|v| vector_of(Some(v))).collect());
+        let vectors = vectors.ok_or_else(|| {
+            FakeError::new(
+                ServerErrorCode::INVALID_PARAMETER,
+                "vectorFloats is required",
+            )
+        })?;
+        let results = self.search(body, &anns, vectors)?;
+        success(json!({ "results": results }))
+    }
+
+    fn select_rows(&self, body: &Value) -> FakeResult {
+        let table = self.table(body)?;
+        let filter = match body.get("filter").and_then(Value::as_str) {
+            Some(expr) => Some(
+                Filter::parse(expr)
+                    .map_err(|e| FakeError::new(ServerErrorCode::INVALID_PARAMETER, e))?,
+            ),
+            None => None,
+        };
+        let start = match body.get("marker") {
+            Some(marker) if !marker.is_null() => Some(table.row_key_of(marker)?),
+            _ => None,
+        };
+        let limit = body.get("limit").and_then(Value::as_u64).unwrap_or(10) as usize;
+        let projections = body.get("projections").and_then(Value::as_array);
+        let pk_name = table.primary_key().unwrap().field_name.clone();
+
+        let mut matched = table
+            .rows
+            .iter()
+            .filter(|(key, _)| start.iter().all(|start| *key >= start))
+            .filter(|(_, row)| filter.iter().all(|f| f.matches(row)));
+        let rows: Vec<Value> = matched
+            .by_ref()
+            .take(limit)
+            .map(|(_, row)| {
+                // vector fields are only selected by projections
+                table.project(row, projections, projections.is_some_and(|p| !p.is_empty()))
+            })
+            .collect();
+        let next = matched.next();
+        let next_marker = match next {
+            Some((_, row)) => json!({ &pk_name: row[&pk_name] }),
+            None => json!({}),
+        };
+        success(json!({
+            "rows": rows,
+            "isTruncated": next.is_some(),
+            "nextMarker": next_marker,
+        }))
+    }
+}
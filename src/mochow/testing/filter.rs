@@ -0,0 +1,412 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+a small evaluator for the SQL WHERE like filter expressions of mochow,
+supports comparison operators, IN, LIKE, AND, OR, NOT and parentheses
+ */
+use std::cmp::Ordering;
+
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(String),
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// parsed filter expression
+#[derive(Debug, Clone)]
+pub(crate) enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Compare(Operand, String, Operand),
+    In(Operand, Vec<Operand>, bool),
+    Like(Operand, String, bool),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Operand {
+    Field(String),
+    Literal(Value),
+}
+
+impl Filter {
+    /// parse the filter expression, error message is returned for invalid syntax
+    pub(crate) fn parse(expr: &str) -> Result<Filter, String> {
+        let tokens = tokenize(expr)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let filter = parser.parse_or()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(format!(
+                "unexpected token {:?} in filter: {}",
+                parser.tokens[parser.pos], expr
+            ));
+        }
+        Ok(filter)
+    }
+
+    /// evaluate the filter against a row, missing fields are treated as null
+    pub(crate) fn matches(&self, row: &Map<String, Value>) -> bool {
+        match self {
+            Filter::And(l, r) => l.matches(row) && r.matches(row),
+            Filter::Or(l, r) => l.matches(row) || r.matches(row),
+            Filter::Not(f) => !f.matches(row),
+            Filter::Compare(l, op, r) => {
+                let (l, r) = (l.value(row), r.value(row));
+                match compare(&l, &r) {
+                    Some(ord) => match op.as_str() {
+                        "=" | "==" => ord == Ordering::Equal,
+                        "!=" | "<>" => ord != Ordering::Equal,
+                        "<" => ord == Ordering::Less,
+                        "<=" => ord != Ordering::Greater,
+                        ">" => ord == Ordering::Greater,
+                        ">=" => ord != Ordering::Less,
+                        _ => false,
+                    },
+                    None => false,
+                }
+            }
+            Filter::In(operand, list, negated) => {
+                let v = operand.value(row);
+                if v.is_null() {
+                    return false;
+                }
+                let found = list
+                    .iter()
+                    .any(|item| compare(&v, &item.value(row)) == Some(Ordering::Equal));
+                found != *negated
+            }
+            Filter::Like(operand, pattern, negated) => match operand.value(row) {
+                Value::String(s) => like(&s, pattern) != *negated,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Operand {
+    fn value(&self, row: &Map<String, Value>) -> Value {
+        match self {
+            Operand::Field(name) => row.get(name).cloned().unwrap_or(Value::Null),
+            Operand::Literal(v) => v.clone(),
+        }
+    }
+}
+
+fn compare(l: &Value, r: &Value) -> Option<Ordering> {
+    match (l, r) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+// sql LIKE with '%' for any sequence and '_' for any single character
+fn like(s: &str, pattern: &str) -> bool {
+    let s: Vec<char> = s.chars().collect();
+    let p: Vec<char> = pattern.chars().collect();
+    let (mut si, mut pi) = (0, 0);
+    let (mut star, mut mark) = (None, 0);
+    while si < s.len() {
+        if pi < p.len() && (p[pi] == '_' || p[pi] == s[si]) {
+            si += 1;
+            pi += 1;
+        } else if pi < p.len() && p[pi] == '%' {
+            star = Some(pi);
+            mark = si;
+            pi += 1;
+        } else if let Some(star) = star {
+            pi = star + 1;
+            mark += 1;
+            si = mark;
+        } else {
+            return false;
+        }
+    }
+    while pi < p.len() && p[pi] == '%' {
+        pi += 1;
+    }
+    pi == p.len()
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '\'' | '"' => {
+                let quote = c;
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(format!("unterminated string in filter: {}", expr)),
+                        Some(&ch) if ch == quote => {
+                            // two quotes in a row is an escaped quote
+                            if chars.get(i + 1) == Some(&quote) {
+                                s.push(quote);
+                                i += 2;
+                            } else {
+                                i += 1;
+                                break;
+                            }
+                        }
+                        Some(&ch) => {
+                            s.push(ch);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            '=' | '!' | '<' | '>' => {
+                let mut op = c.to_string();
+                if let Some(&next) = chars.get(i + 1) {
+                    if next == '=' || (c == '<' && next == '>') {
+                        op.push(next);
+                    }
+                }
+                if op == "!" {
+                    return Err(format!("invalid operator '!' in filter: {}", expr));
+                }
+                i += op.len();
+                tokens.push(Token::Op(op));
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let start = i;
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || chars[i] == '.'
+                        || chars[i] == 'e'
+                        || chars[i] == 'E')
+                {
+                    i += 1;
+                }
+                let s: String = chars[start..i].iter().collect();
+                let n = s
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid number {} in filter: {}", s, expr))?;
+                tokens.push(Token::Num(n));
+            }
+            c if c.is_alphabetic() || c == '_' || c == '`' => {
+                let quoted = c == '`';
+                let start = if quoted { i + 1 } else { i };
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_')
+                    && !(quoted && chars[i] == '`')
+                {
+                    i += 1;
+                }
+                let s: String = chars[start..i].iter().collect();
+                if quoted {
+                    if chars.get(i) != Some(&'`') {
+                        return Err(format!("unterminated identifier in filter: {}", expr));
+                    }
+                    i += 1;
+                }
+                tokens.push(Token::Ident(s));
+            }
+            _ => return Err(format!("unexpected character '{}' in filter: {}", c, expr)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Ident(s)) if s.eq_ignore_ascii_case(keyword))
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, String> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Filter::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, String> {
+        let mut left = self.parse_not()?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            let right = self.parse_not()?;
+            left = Filter::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Filter, String> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Filter::Not(Box::new(self.parse_not()?)));
+        }
+        if self.tokens.get(self.pos) == Some(&Token::LParen) {
+            self.pos += 1;
+            let filter = self.parse_or()?;
+            if self.tokens.get(self.pos) != Some(&Token::RParen) {
+                return Err("missing ')' in filter".to_string());
+            }
+            self.pos += 1;
+            return Ok(filter);
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> Result<Filter, String> {
+        let left = self.parse_operand()?;
+        let negated = self.peek_keyword("not");
+        if negated {
+            self.pos += 1;
+        }
+        if self.peek_keyword("in") {
+            self.pos += 1;
+            if self.tokens.get(self.pos) != Some(&Token::LParen) {
+                return Err("missing '(' after IN in filter".to_string());
+            }
+            self.pos += 1;
+            let mut list = vec![self.parse_operand()?];
+            while self.tokens.get(self.pos) == Some(&Token::Comma) {
+                self.pos += 1;
+                list.push(self.parse_operand()?);
+            }
+            if self.tokens.get(self.pos) != Some(&Token::RParen) {
+                return Err("missing ')' after IN list in filter".to_string());
+            }
+            self.pos += 1;
+            return Ok(Filter::In(left, list, negated));
+        }
+        if self.peek_keyword("like") {
+            self.pos += 1;
+            return match self.tokens.get(self.pos) {
+                Some(Token::Str(pattern)) => {
+                    let pattern = pattern.clone();
+                    self.pos += 1;
+                    Ok(Filter::Like(left, pattern, negated))
+                }
+                _ => Err("LIKE requires a string pattern in filter".to_string()),
+            };
+        }
+        if negated {
+            return Err("NOT must be followed by IN or LIKE in filter".to_string());
+        }
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) => {
+                let op = op.clone();
+                self.pos += 1;
+                let right = self.parse_operand()?;
+                Ok(Filter::Compare(left, op, right))
+            }
+            other => Err(format!("expect comparison operator, got {:?}", other)),
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "unexpected end of filter".to_string())?;
+        self.pos += 1;
+        match token {
+            Token::Str(s) => Ok(Operand::Literal(Value::String(s))),
+            Token::Num(n) => Ok(Operand::Literal(serde_json::json!(n))),
+            Token::Ident(s) if s.eq_ignore_ascii_case("true") => {
+                Ok(Operand::Literal(Value::Bool(true)))
+            }
+            Token::Ident(s) if s.eq_ignore_ascii_case("false") => {
+                Ok(Operand::Literal(Value::Bool(false)))
+            }
+            Token::Ident(s) => Ok(Operand::Field(s)),
+            other => Err(format!("unexpected token {:?} in filter", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row() -> Map<String, Value> {
+        serde_json::json!({
+            "id": "0001",
+            "bookName": "三国演义",
+            "author": "罗贯中",
+            "page": 22,
+            "available": true,
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let cases = [
+            ("page >= 22", true),
+            ("page > 22", false),
+            ("bookName = '三国演义' AND page < 30", true),
+            ("bookName = '西游记' or page <> 21", true),
+            ("NOT (page = 22)", false),
+            ("author IN ('吴承恩', '罗贯中')", true),
+            ("author NOT IN ('吴承恩', '罗贯中')", false),
+            ("id LIKE '00%'", true),
+            ("id like '_002'", false),
+            ("available = true and missing = 1", false),
+            ("page = 22.0", true),
+        ];
+        for (expr, expected) in cases {
+            let filter = Filter::parse(expr).unwrap();
+            assert_eq!(filter.matches(&row()), expected, "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_filter_syntax_error() {
+        for expr in ["page >", "(page = 1", "page ! 1", "'abc", "page = 1 extra"] {
+            assert!(Filter::parse(expr).is_err(), "{}", expr);
+        }
+    }
+}
@@ -0,0 +1,436 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+in-memory backend implementing MochowApi without http
+ */
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::{
+    error::SdkError,
+    mochow::{
+        api::*,
+        client::{DryRunRequest, IntoRequest},
+        config::{ClientConfiguration, ClientConfigurationBuilder},
+        mochow_api::MochowApi,
+    },
+};
+
+use super::engine::{FakeError, FakeMochow};
+
+impl From<FakeError> for SdkError {
+    fn from(value: FakeError) -> Self {
+        SdkError::ServiceError(ServiceError {
+            status_code: value.status_code() as i32,
+            request_id: "".to_string(),
+            resp: CommonResponse {
+                code: value.code.clone() as i32,
+                msg: value.msg,
+            },
+            server_code: value.code,
+        })
+    }
+}
+
+/// an embedded mochow serving all the operations of [MochowApi] from memory,
+/// with the same validation and errors as the server, clones share the same data
+/// ```rust,ignore
+/// use std::sync::Arc;
+/// use mochow_sdk_rust::mochow::{mochow_api::MochowApi, testing::MemoryBackend};
+///
+/// let api: Arc<dyn MochowApi> = Arc::new(MemoryBackend::new());
+/// api.create_database("book").await.unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemoryBackend {
+    state: Arc<Mutex<FakeMochow>>,
+    // only used to build the requests, nothing is sent
+    configuration: ClientConfiguration,
+    http_client: ClientWithMiddleware,
+}
+
+impl Default for MemoryBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        let configuration = ClientConfigurationBuilder::default()
+            .account("root")
+            .api_key("memory")
+            .endpoint("http://memory")
+            .build()
+            .unwrap();
+        Self {
+            state: Arc::new(Mutex::new(FakeMochow::new())),
+            configuration,
+            http_client: ClientBuilder::new(reqwest::Client::new()).build(),
+        }
+    }
+
+    /// clear all the databases
+    pub fn reset(&self) {
+        *self.state.lock().unwrap() = FakeMochow::new();
+    }
+
+    fn handle<T: DeserializeOwned>(&self, args: impl IntoRequest) -> Result<T, SdkError> {
+        let req = args
+            .into_request(&self.configuration, &self.http_client)
+            .build()?;
+        let req = DryRunRequest::from_request(&req);
+        let (path, query) = req.path.split_once('?').unwrap_or((&req.path, ""));
+        let body = if req.body.is_null() {
+            json!({})
+        } else {
+            req.body.clone()
+        };
+        let resp = self
+            .state
+            .lock()
+            .unwrap()
+            .handle(&req.method, path, query, body)?;
+        serde_json::from_value(resp).map_err(|e| SdkError::OtherError(e.into()))
+    }
+}
+
+#[async_trait]
+impl MochowApi for MemoryBackend {
+    async fn create_database(&self, data_base: &str) -> Result<CommonResponse, SdkError> {
+        let args = CreateDatabaseArgsBuilder::default()
+            .database(data_base)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn drop_database(&self, data_base: &str) -> Result<CommonResponse, SdkError> {
+        let args = DropDatabaseArgsBuilder::default()
+            .database(data_base)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn list_database(&self) -> Result<ListDatabaseResponse, SdkError> {
+        let args = ListDatabaseArgsBuilder::default().build()?;
+        self.handle(args)
+    }
+
+    async fn has_database(&self, data_base: &str) -> Result<bool, SdkError> {
+        let ret = self.list_database().await?;
+        Ok(ret.databases.contains(&data_base.to_string()))
+    }
+
+    async fn create_table(&self, args: &CreateTableArgs) -> Result<CommonResponse, SdkError> {
+        args.validate()?;
+        self.handle(args.clone())
+    }
+
+    async fn drop_table(&self, data_base: &str, table: &str) -> Result<CommonResponse, SdkError> {
+        let args = DropTableArgsBuilder::default()
+            .database(data_base)
+            .table(table)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn list_table(&self, data_base: &str) -> Result<ListTableResponse, SdkError> {
+        let args = ListTableArgsBuilder::default()
+            .database(data_base)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn has_table(&self, data_base: &str, table: &str) -> Result<bool, SdkError> {
+        let ret = self.list_table(data_base).await?;
+        Ok(ret.tables.contains(&table.to_string()))
+    }
+
+    async fn desc_table(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<DescriptTableResponse, SdkError> {
+        let args = DescriptTableArgsBuilder::default()
+            .database(data_base)
+            .table(table)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn add_field(&self, args: &AddFieldArgs) -> Result<CommonResponse, SdkError> {
+        self.handle(args.clone())
+    }
+
+    async fn show_table_stats(
+        &self,
+        data_base: &str,
+        table: &str,
+    ) -> Result<StatsTableResponse, SdkError> {
+        let args = StatsTableArgsBuilder::default()
+            .database(data_base)
+            .table(table)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn alias_table(
+        &self,
+        data_base: &str,
+        table: &str,
+        alias: &str,
+    ) -> Result<CommonResponse, SdkError> {
+        let args = AliasTableArgsBuilder::default()
+            .database(data_base)
+            .table(table)
+            .alias(alias)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn unalias_table(
+        &self,
+        data_base: &str,
+        table: &str,
+        alias: &str,
+    ) -> Result<CommonResponse, SdkError> {
+        let args = UnaliasTableArgsBuilder::default()
+            .database(data_base)
+            .table(table)
+            .alias(alias)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn create_index(&self, args: &CreateIndexArgs) -> Result<CommonResponse, SdkError> {
+        let schema = self
+            .desc_table(&args.database, &args.table)
+            .await?
+            .table
+            .schema;
+        args.validate(Some(&schema))?;
+        self.handle(args.clone())
+    }
+
+    async fn desc_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+    ) -> Result<DescriptIndexResponse, SdkError> {
+        let args = DescriptIndexArgsBuilder::default()
+            .database(data_base)
+            .table(table)
+            .index_name(index_name)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn modify_index(&self, args: &ModifyIndexArgs) -> Result<CommonResponse, SdkError> {
+        self.handle(args.clone())
+    }
+
+    async fn rebuild_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+    ) -> Result<CommonResponse, SdkError> {
+        let args = RebuildIndexArgsBuilder::default()
+            .database(data_base)
+            .table(table)
+            .index_name(index_name)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn delete_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+    ) -> Result<CommonResponse, SdkError> {
+        let args = DeleteIndexArgsBuilder::default()
+            .database(data_base)
+            .table(table)
+            .index_name(index_name)
+            .build()?;
+        self.handle(args)
+    }
+
+    async fn insert_row(
+        &self,
+        args: &InsertRowArgs<Value>,
+    ) -> Result<InsertRowsResponse, SdkError> {
+        self.handle(args.clone())
+    }
+
+    async fn upsert_row(
+        &self,
+        args: &UpsertRowArgs<Value>,
+    ) -> Result<UpsertRowsResponse, SdkError> {
+        self.handle(args.clone())
+    }
+
+    async fn update_row(&self, args: &UpdateRowArgs) -> Result<CommonResponse, SdkError> {
+        self.handle(args.clone())
+    }
+
+    async fn delete_rows(&self, args: &DeleteRowArgs) -> Result<CommonResponse, SdkError> {
+        self.handle(args.clone())
+    }
+
+    async fn query_row(&self, args: &QueryRowArgs) -> Result<QueryRowsResponse<Value>, SdkError> {
+        self.handle(args.clone())
+    }
+
+    async fn search_rows(
+        &self,
+        args: &SearchRowsArgs,
+    ) -> Result<SearchRowsResponse<Value>, SdkError> {
+        self.handle(args.clone())
+    }
+
+    async fn select_rows(
+        &self,
+        args: &SelectRowsArgs,
+    ) -> Result<SelectRowsResponse<Value>, SdkError> {
+        self.handle(args.clone())
+    }
+
+    async fn batch_search_rows(
+        &self,
+        args: &BatchSearchRowsArgs,
+    ) -> Result<BatchSearchRowsResponse<Value>, SdkError> {
+        self.handle(args.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    fn assert_server_code(ret: Result<impl std::fmt::Debug, SdkError>, code: ServerErrorCode) {
+        match ret {
+            Err(SdkError::ServiceError(e)) => {
+                assert_eq!(e.server_code as i32, code as i32, "{}", e.resp.msg)
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_validation() -> Result<()> {
+        let api: Arc<dyn MochowApi> = Arc::new(MemoryBackend::new());
+        api.create_database("book").await?;
+        let fields = vec![
+            FieldSchemaBuilder::default()
+                .field_name("id")
+                .field_type(FieldType::UINT64)
+                .primary_key(true)
+                .partition_key(true)
+                .auto_increment(true)
+                .not_null(true)
+                .build()?,
+            FieldSchemaBuilder::default()
+                .field_name("bookName")
+                .field_type(FieldType::STRING)
+                .not_null(true)
+                .build()?,
+            FieldSchemaBuilder::default()
+                .field_name("vector")
+                .field_type(FieldType::FLOAT_VECTOR)
+                .not_null(true)
+                .dimension(2)
+                .build()?,
+        ];
+        let args = CreateTableArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .description("memory backend test")
+            .replication(1_u32)
+            .partition(Partition {
+                partition_type: PartitionType::HASH,
+                partition_num: 1,
+            })
+            .schema(TableSchema {
+                fields,
+                indexes: vec![],
+            })
+            .build()?;
+        api.create_table(&args).await?;
+        assert!(api.has_table("book", "segments").await?);
+
+        let insert = |row: Value| {
+            InsertRowArgsBuilder::default()
+                .database("book")
+                .table("segments")
+                .rows(vec![row])
+                .build()
+        };
+        let ret = api
+            .insert_row(&insert(json!({"bookName": "a", "vector": [0.1, 0.2]}))?)
+            .await?;
+        assert_eq!(ret.affected_count, 1);
+        let ret = api
+            .insert_row(&insert(
+                json!({"bookName": "b", "vector": [0.1, 0.2, 0.3]}),
+            )?)
+            .await;
+        assert_server_code(ret, ServerErrorCode::INVALID_PARAMETER);
+        let ret = api
+            .insert_row(&insert(json!({"vector": [0.1, 0.2]}))?)
+            .await;
+        assert_server_code(ret, ServerErrorCode::INVALID_PARAMETER);
+        let ret = api
+            .insert_row(&insert(
+                json!({"id": 1, "bookName": "c", "vector": [0.3, 0.4]}),
+            )?)
+            .await;
+        assert_server_code(ret, ServerErrorCode::PRIMARY_KEY_DUPLICATED);
+
+        let args = QueryRowArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .primary_key(json!({"id": 1}))
+            .retrieve_vector(true)
+            .build()?;
+        let ret = api.query_row(&args).await?;
+        assert_eq!(ret.row["bookName"], "a");
+        assert_eq!(ret.row["vector"], json!([0.1, 0.2]));
+
+        let args = UpdateRowArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .primary_key(json!({"id": 1}))
+            .update(json!({"bookName": null}))
+            .build()?;
+        assert_server_code(
+            api.update_row(&args).await,
+            ServerErrorCode::INVALID_PARAMETER,
+        );
+        assert_server_code(
+            api.drop_table("book", "missing").await,
+            ServerErrorCode::TABLE_NOT_EXIST,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+in-process fake mochow server and in-memory backend for tests,
+the server is enabled by the `testing` feature, the in-memory backend by `memory-backend` or `testing`
+ */
+mod engine;
+mod filter;
+mod memory;
+#[cfg(any(test, feature = "testing"))]
+mod server;
+
+pub use engine::{FakeError, FakeMochow};
+pub use memory::MemoryBackend;
+#[cfg(any(test, feature = "testing"))]
+pub use server::FakeMochowServer;
+
+#[cfg(test)]
+use super::api::*;
+
+/// a field of the test tables, `id` is the primary key and partition key,
+/// `id` and `vector` are not null, `vector` has dimension 2
+#[cfg(test)]
+pub(crate) fn field(name: &str, field_type: FieldType) -> FieldSchema {
+    FieldSchemaBuilder::default()
+        .field_name(name)
+        .field_type(field_type)
+        .primary_key(name == "id")
+        .partition_key(name == "id")
+        .not_null(matches!(name, "id" | "vector"))
+        .dimension(if name == "vector" { Some(2) } else { None })
+        .build()
+        .unwrap()
+}
+
+/// args to create the test table in database book, with 1 HASH partition and replication 1
+#[cfg(test)]
+pub(crate) fn create_table_args(
+    table: &str,
+    fields: Vec<FieldSchema>,
+    indexes: Vec<IndexSchema>,
+) -> CreateTableArgs {
+    CreateTableArgsBuilder::default()
+        .database("book")
+        .table(table)
+        .description("")
+        .replication(1_u32)
+        .partition(Partition {
+            partition_type: PartitionType::HASH,
+            partition_num: 1,
+        })
+        .schema(TableSchema { fields, indexes })
+        .build()
+        .unwrap()
+}
@@ -0,0 +1,303 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+fake mochow server serving the engine over http, enabled by the `testing` feature
+ */
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::{Bytes, Incoming},
+    server::conn::http1,
+    service::service_fn,
+    Request, Response,
+};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{error::SdkError, mochow::api::ServerErrorCode, mochow::client::MochowClient};
+
+use super::engine::{FakeError, FakeMochow};
+
+/// a mochow server serving the database, table, index and row apis from memory,
+/// it listens on a random local port and stops when dropped
+/// ```rust,ignore
+/// use mochow_sdk_rust::mochow::testing::FakeMochowServer;
+///
+/// let server = FakeMochowServer::start().await.unwrap();
+/// let client = server.client().unwrap();
+/// client.create_database("book").await.unwrap();
+/// ```
+pub struct FakeMochowServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<FakeMochow>>,
+    task: JoinHandle<()>,
+}
+
+impl FakeMochowServer {
+    /// start the server on a random port of 127.0.0.1, must be called in a tokio runtime
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(FakeMochow::new()));
+        let request_seq = Arc::new(AtomicU64::new(0));
+        let server_state = state.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    continue;
+                };
+                let state = server_state.clone();
+                let request_seq = request_seq.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |req| {
+                        let request_id =
+                            format!("fake-{}", request_seq.fetch_add(1, Ordering::Relaxed) + 1);
+                        serve(state.clone(), req, request_id)
+                    });
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+        Ok(Self { addr, state, task })
+    }
+
+    /// endpoint of the server, like http://127.0.0.1:12345
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// a client connected to the server, any account and api key are accepted
+    pub fn client(&self) -> Result<MochowClient, SdkError> {
+        MochowClient::new("root", "fake_api_key", &self.endpoint())
+    }
+
+    /// clear all the databases of the server
+    pub fn reset(&self) {
+        *self.state.lock().unwrap() = FakeMochow::new();
+    }
+
+    /// number of desc_index polls an index reports BUILDING after it's created or rebuilt,
+    /// see [FakeMochow::set_index_build_polls]
+    pub fn set_index_build_polls(&self, polls: u64) {
+        self.state.lock().unwrap().set_index_build_polls(polls);
+    }
+
+    /// vector indexes of the table not rebuilt since rows are written,
+    /// see [FakeMochow::stale_indexes]
+    pub fn stale_indexes(&self, database: &str, table: &str) -> Vec<String> {
+        self.state.lock().unwrap().stale_indexes(database, table)
+    }
+
+    /// number of desc_index polls a deleted index is still described,
+    /// see [FakeMochow::set_index_delete_polls]
+    pub fn set_index_delete_polls(&self, polls: u64) {
+        self.state.lock().unwrap().set_index_delete_polls(polls);
+    }
+}
+
+impl Drop for FakeMochowServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(
+    state: Arc<Mutex<FakeMochow>>,
+    req: Request<Incoming>,
+    request_id: String,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let method = req.method().as_str().to_string();
+    let path = req.uri().path().to_string();
+    let query = req.uri().query().unwrap_or_default().to_string();
+    let authorized = req.headers().contains_key(hyper::header::AUTHORIZATION);
+    let body = req
+        .into_body()
+        .collect()
+        .await
+        .map(|b| b.to_bytes())
+        .unwrap_or_default();
+
+    let result = if !authorized {
+        Err(FakeError::new(
+            ServerErrorCode::AUTHENTICATION_FAILED,
+            "authorization header is required",
+        ))
+    } else if body.is_empty() {
+        state
+            .lock()
+            .unwrap()
+            .handle(&method, &path, &query, Value::Object(Default::default()))
+    } else {
+        match serde_json::from_slice::<Value>(&body) {
+            Ok(body) => state.lock().unwrap().handle(&method, &path, &query, body),
+            Err(e) => Err(FakeError::new(
+                ServerErrorCode::INVALID_HTTP_BODY,
+                format!("invalid json body: {}", e),
+            )),
+        }
+    };
+    let (status, body) = match result {
+        Ok(body) => (200, body),
+        Err(e) => (e.status_code(), e.to_json()),
+    };
+    let resp = Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .header("Request-ID", request_id)
+        .body(Full::new(Bytes::from(body.to_string())))
+        .unwrap();
+    Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+
+    use super::*;
+    use crate::mochow::api::*;
+
+    async fn create_book_table(client: &MochowClient) -> Result<()> {
+        client.create_database("book").await?;
+        let fields = vec![
+            FieldSchemaBuilder::default()
+                .field_name("id")
+                .field_type(FieldType::STRING)
+                .primary_key(true)
+                .partition_key(true)
+                .not_null(true)
+                .build()?,
+            FieldSchemaBuilder::default()
+                .field_name("page")
+                .field_type(FieldType::UINT32)
+                .build()?,
+            FieldSchemaBuilder::default()
+                .field_name("vector")
+                .field_type(FieldType::FLOAT_VECTOR)
+                .not_null(true)
+                .dimension(3)
+                .build()?,
+        ];
+        let indexes = vec![IndexSchemaBuilder::default()
+            .index_name("vector_idx")
+            .index_type(IndexType::HNSW)
+            .field("vector")
+            .metric_type(MetricType::L2)
+            .params(VectorIndexParams::HNSW(HNSWIndexParam {
+                m: 16,
+                ef_construction: 200,
+            }))
+            .build()?];
+        let args = CreateTableArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .description("fake server test")
+            .replication(1_u32)
+            .partition(Partition {
+                partition_type: PartitionType::HASH,
+                partition_num: 1,
+            })
+            .schema(TableSchema { fields, indexes })
+            .build()?;
+        client.create_table(&args).await?;
+        let rows: Vec<Value> = (0..5)
+            .map(|i| json!({"id": format!("{:04}", i), "page": i, "vector": [i as f32, 0.0, 0.0]}))
+            .collect();
+        let args = InsertRowArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .rows(rows)
+            .build()?;
+        client.insert_row(&args).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fake_server_end_to_end() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        create_book_table(&client).await?;
+        assert_eq!(client.list_database().await?.databases, vec!["book"]);
+
+        let ret = client.create_database("book").await;
+        assert!(matches!(
+            ret,
+            Err(SdkError::ServiceError(ref e))
+                if e.status_code == 409 && matches!(e.server_code, ServerErrorCode::DB_ALREADY_EXIST)
+        ));
+
+        let args = SearchRowsArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .anns(
+                AnnsSearchParamsBuilder::default()
+                    .vector_field("vector")
+                    .vector_floats(vec![3.2, 0.0, 0.0])
+                    .filter("page >= 1")
+                    .params(VectorSearchParams::HNSW(HNSWSearchParams {
+                        ef: 200,
+                        limit: 2,
+                        distance_far: None,
+                        distance_near: None,
+                        pruning: false,
+                    }))
+                    .build()?,
+            )
+            .build()?;
+        let ret: SearchRowsResponse<Value> = client.search_rows(&args).await?;
+        let ids: Vec<&Value> = ret.rows.iter().map(|r| &r.row["id"]).collect();
+        assert_eq!(ids, vec!["0003", "0004"]);
+
+        let mut marker = None;
+        let mut pages = 0;
+        let mut ids = Vec::new();
+        loop {
+            let mut builder = SelectRowsArgsBuilder::default();
+            builder.database("book").table("segments").limit(2_u32);
+            if let Some(marker) = marker.take() {
+                builder.marker(marker);
+            }
+            let ret: SelectRowsResponse<Value> = client.select_rows(&builder.build()?).await?;
+            pages += 1;
+            ids.extend(ret.rows.iter().map(|r| r["id"].clone()));
+            if !ret.is_truncated {
+                break;
+            }
+            marker = Some(ret.next_marker);
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(ids.len(), 5);
+
+        let ret = client.drop_database("book").await;
+        assert!(matches!(
+            ret,
+            Err(SdkError::ServiceError(ref e)) if matches!(e.server_code, ServerErrorCode::DB_NOT_EMPTY)
+        ));
+        server.reset();
+        assert!(client.list_database().await?.databases.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,817 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+client side validation of request args, to report mistakes before they are sent to the server
+ */
+use std::{collections::HashSet, error::Error, fmt::Display};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::api::{
+    CreateIndexArgs, CreateTableArgs, DescriptTable, FieldSchema, FieldType, IndexSchema,
+    IndexType, InsertRowArgs, TableSchema, UpsertRowArgs, VectorIndexParams,
+};
+
+/// a rule broken by the args
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// location of the invalid value, like "schema.fields[2].dimension"
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// all the violations found by validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub violations: Vec<Violation>,
+}
+
+impl Error for ValidationError {}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let violations: Vec<String> = self.violations.iter().map(|v| v.to_string()).collect();
+        write!(f, "{}", violations.join("; "))
+    }
+}
+
+/// collector of violations
+#[derive(Debug, Default)]
+pub(crate) struct Violations(Vec<Violation>);
+
+impl Violations {
+    pub(crate) fn add(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(Violation {
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    pub(crate) fn into_result(self) -> Result<(), ValidationError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { violations: self.0 })
+        }
+    }
+}
+
+/// must start with a letter, only letters, numbers and underscores are allowed
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// primary key and partition key are not supported on these types
+fn is_key_type(field_type: &FieldType) -> bool {
+    !matches!(
+        field_type,
+        FieldType::BOOL | FieldType::FLOAT | FieldType::DOUBLE | FieldType::FLOAT_VECTOR
+    )
+}
+
+impl CreateTableArgs {
+    /// check the args against the rules of the server, all violations are returned at once,
+    /// it's called by [MochowClient::create_table] before sending
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+        if !(1..=10).contains(&self.replication) {
+            violations.add("replication", "should be in range [1, 10]");
+        }
+        if !(1..=1000).contains(&self.partition.partition_num) {
+            violations.add("partition.partitionNum", "should be in range [1, 1000]");
+        }
+        self.schema.check(&mut violations, "schema.");
+        violations.into_result()
+    }
+}
+
+impl TableSchema {
+    /// check the fields and indexes against the rules of the server, all violations are returned at once
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+        self.check(&mut violations, "");
+        violations.into_result()
+    }
+
+    pub(crate) fn check(&self, violations: &mut Violations, prefix: &str) {
+        let mut names = HashSet::new();
+        let mut primary_keys = 0;
+        let mut partition_keys = 0;
+        for (i, field) in self.fields.iter().enumerate() {
+            let path = format!("{}fields[{}]", prefix, i);
+            if !is_valid_name(&field.field_name) {
+                violations.add(
+                    format!("{}.fieldName", path),
+                    format!(
+                        "invalid field name {:?}, must start with a letter, only letters, numbers and underscores are allowed",
+                        field.field_name
+                    ),
+                );
+            }
+            if !names.insert(field.field_name.as_str()) {
+                violations.add(
+                    format!("{}.fieldName", path),
+                    format!("duplicated field name {}", field.field_name),
+                );
+            }
+            if field.primary_key {
+                primary_keys += 1;
+                if !is_key_type(&field.field_type) {
+                    violations.add(
+                        format!("{}.primaryKey", path),
+                        format!("{:?} field can not be primary key", field.field_type),
+                    );
+                }
+            }
+            if field.partition_key {
+                partition_keys += 1;
+                if !is_key_type(&field.field_type) {
+                    violations.add(
+                        format!("{}.partitionKey", path),
+                        format!("{:?} field can not be partition key", field.field_type),
+                    );
+                }
+            }
+            if field.auto_increment
+                && !(field.primary_key && matches!(field.field_type, FieldType::UINT64))
+            {
+                violations.add(
+                    format!("{}.autoIncrement", path),
+                    "only applicable to primary key of type UINT64",
+                );
+            }
+            match (&field.field_type, field.dimension) {
+                (FieldType::FLOAT_VECTOR, None | Some(0)) => violations.add(
+                    format!("{}.dimension", path),
+                    "FLOAT_VECTOR field requires a positive dimension",
+                ),
+                (FieldType::FLOAT_VECTOR, _) | (_, None) => {}
+                (_, Some(_)) => violations.add(
+                    format!("{}.dimension", path),
+                    "only applicable to FLOAT_VECTOR field",
+                ),
+            }
+        }
+        if primary_keys != 1 {
+            violations.add(
+                format!("{}fields", prefix),
+                format!("exactly one primary key is required, got {}", primary_keys),
+            );
+        }
+        if partition_keys > 1 {
+            violations.add(
+                format!("{}fields", prefix),
+                format!(
+                    "at most one partition key is allowed, got {}",
+                    partition_keys
+                ),
+            );
+        }
+        for (i, index) in self.indexes.iter().enumerate() {
+            index.check(
+                violations,
+                &format!("{}indexes[{}]", prefix, i),
+                Some(&self.fields),
+            );
+        }
+    }
+}
+
+impl CreateIndexArgs {
+    /// check the indexes against the rules of the server, all violations are returned at once,
+    /// the target fields are checked too if the schema of the table is given,
+    /// it's called by [MochowClient::create_index] before sending
+    pub fn validate(&self, schema: Option<&TableSchema>) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+        for (i, index) in self.indexes.iter().enumerate() {
+            index.check(
+                &mut violations,
+                &format!("indexes[{}]", i),
+                schema.map(|s| s.fields.as_slice()),
+            );
+        }
+        violations.into_result()
+    }
+}
+
+fn in_range(
+    violations: &mut Violations,
+    path: String,
+    value: u32,
+    range: std::ops::RangeInclusive<u32>,
+) {
+    if !range.contains(&value) {
+        violations.add(
+            path,
+            format!(
+                "{} is out of range [{}, {}]",
+                value,
+                range.start(),
+                range.end()
+            ),
+        );
+    }
+}
+
+impl IndexSchema {
+    // check the index type, metric type and params, and the target field if the fields are known
+    pub(crate) fn check(
+        &self,
+        violations: &mut Violations,
+        path: &str,
+        fields: Option<&[FieldSchema]>,
+    ) {
+        let Some(index_type) = &self.index_type else {
+            violations.add(format!("{}.indexType", path), "index type is required");
+            return;
+        };
+        let is_vector = !matches!(index_type, IndexType::SECONDARY_INDEX);
+        if is_vector && self.metric_type.is_none() {
+            violations.add(
+                format!("{}.metricType", path),
+                "metric type is required for vector index",
+            );
+        }
+        let params_path = format!("{}.params", path);
+        match (index_type, &self.params) {
+            // params unknown to the sdk, like described with new fields, are left to the server
+            (_, Some(VectorIndexParams::Raw(_))) if is_vector => {}
+            (IndexType::FLAT | IndexType::SECONDARY_INDEX, None)
+            | (IndexType::FLAT, Some(VectorIndexParams::FLAT(_))) => {}
+            (IndexType::FLAT | IndexType::SECONDARY_INDEX, Some(_)) => violations.add(
+                params_path,
+                format!("params is not applicable to {:?} index", index_type),
+            ),
+            (IndexType::HNSW, Some(VectorIndexParams::HNSW(params))) => {
+                in_range(violations, format!("{}.M", params_path), params.m, 4..=128);
+                in_range(
+                    violations,
+                    format!("{}.efConstruction", params_path),
+                    params.ef_construction,
+                    8..=1024,
+                );
+            }
+            (IndexType::HNSWPQ, Some(VectorIndexParams::HNSWPQ(params))) => {
+                in_range(violations, format!("{}.M", params_path), params.m, 4..=128);
+                in_range(
+                    violations,
+                    format!("{}.efConstruction", params_path),
+                    params.ef_construction,
+                    8..=1024,
+                );
+                if params.nsq == 0 {
+                    violations.add(format!("{}.NSQ", params_path), "should be positive");
+                }
+                if !(0.0..=1.0).contains(&params.sample_rate) {
+                    violations.add(
+                        format!("{}.sampleRate", params_path),
+                        format!("{} is out of range [0, 1]", params.sample_rate),
+                    );
+                }
+            }
+            (IndexType::PUCK, Some(VectorIndexParams::PUCK(params))) => {
+                in_range(
+                    violations,
+                    format!("{}.coarseClusterCount", params_path),
+                    params.coarse_cluster_count,
+                    1..=5000,
+                );
+                in_range(
+                    violations,
+                    format!("{}.fineClusterCount", params_path),
+                    params.fine_cluster_count,
+                    1..=5000,
+                );
+            }
+            (_, None) => violations.add(
+                params_path,
+                format!("params is required for {:?} index", index_type),
+            ),
+            (_, Some(_)) => violations.add(
+                params_path,
+                format!("params does not match {:?} index", index_type),
+            ),
+        }
+
+        let Some(fields) = fields else {
+            return;
+        };
+        let field_path = format!("{}.field", path);
+        let Some(field) = fields.iter().find(|f| f.field_name == self.field) else {
+            violations.add(field_path, format!("unknown field {}", self.field));
+            return;
+        };
+        match (is_vector, &field.field_type) {
+            (true, FieldType::FLOAT_VECTOR) => {
+                if let (Some(VectorIndexParams::HNSWPQ(params)), Some(dimension)) =
+                    (&self.params, field.dimension)
+                {
+                    if params.nsq > 0 && dimension % params.nsq != 0 {
+                        violations.add(
+                            format!("{}.params.NSQ", path),
+                            format!(
+                                "{} does not divide the dimension {} of field {}",
+                                params.nsq, dimension, field.field_name
+                            ),
+                        );
+                    }
+                }
+            }
+            (true, _) => violations.add(
+                field_path,
+                format!(
+                    "{:?} index requires a FLOAT_VECTOR field, {} is {:?}",
+                    index_type, field.field_name, field.field_type
+                ),
+            ),
+            (false, FieldType::FLOAT_VECTOR) => violations.add(
+                field_path,
+                format!(
+                    "secondary index is not applicable to FLOAT_VECTOR field {}",
+                    field.field_name
+                ),
+            ),
+            (false, _) => {}
+        }
+    }
+}
+
+impl TableSchema {
+    /// check the existing schema of a table matches this one, like the schema returned by
+    /// [crate::mochow::client::MochowClient::desc_table], fields and indexes are matched by name,
+    /// server side state of indexes is ignored
+    pub fn verify_matches(&self, existing: &TableSchema) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+        for (i, field) in self.fields.iter().enumerate() {
+            let path = format!("fields[{}]", i);
+            let Some(other) = existing
+                .fields
+                .iter()
+                .find(|f| f.field_name == field.field_name)
+            else {
+                violations.add(path, format!("field {} does not exist", field.field_name));
+                continue;
+            };
+            let mut differs = |name: &str, requested: String, actual: String| {
+                if requested != actual {
+                    violations.add(
+                        format!("{}.{}", path, name),
+                        format!("requested {}, but existing {}", requested, actual),
+                    );
+                }
+            };
+            differs(
+                "fieldType",
+                format!("{:?}", field.field_type),
+                format!("{:?}", other.field_type),
+            );
+            differs(
+                "primaryKey",
+                field.primary_key.to_string(),
+                other.primary_key.to_string(),
+            );
+            differs(
+                "partitionKey",
+                field.partition_key.to_string(),
+                other.partition_key.to_string(),
+            );
+            differs(
+                "autoIncrement",
+                field.auto_increment.to_string(),
+                other.auto_increment.to_string(),
+            );
+            differs(
+                "notNull",
+                field.not_null.to_string(),
+                other.not_null.to_string(),
+            );
+            differs(
+                "dimension",
+                format!("{:?}", field.dimension),
+                format!("{:?}", other.dimension),
+            );
+        }
+        for field in &existing.fields {
+            if !self.fields.iter().any(|f| f.field_name == field.field_name) {
+                violations.add(
+                    "fields",
+                    format!("existing field {} is not requested", field.field_name),
+                );
+            }
+        }
+        for (i, index) in self.indexes.iter().enumerate() {
+            let path = format!("indexes[{}]", i);
+            let Some(other) = existing
+                .indexes
+                .iter()
+                .find(|x| x.index_name == index.index_name)
+            else {
+                violations.add(path, format!("index {} does not exist", index.index_name));
+                continue;
+            };
+            let mut differs = |name: &str, requested: String, actual: String| {
+                if requested != actual {
+                    violations.add(
+                        format!("{}.{}", path, name),
+                        format!("requested {}, but existing {}", requested, actual),
+                    );
+                }
+            };
+            differs("field", index.field.clone(), other.field.clone());
+            differs(
+                "indexType",
+                format!("{:?}", index.index_type),
+                format!("{:?}", other.index_type),
+            );
+            differs(
+                "metricType",
+                format!("{:?}", index.metric_type),
+                format!("{:?}", other.metric_type),
+            );
+            differs(
+                "params",
+                serde_json::to_string(&index.params).unwrap_or_default(),
+                serde_json::to_string(&other.params).unwrap_or_default(),
+            );
+        }
+        for index in &existing.indexes {
+            if !self
+                .indexes
+                .iter()
+                .any(|x| x.index_name == index.index_name)
+            {
+                violations.add(
+                    "indexes",
+                    format!("existing index {} is not requested", index.index_name),
+                );
+            }
+        }
+        violations.into_result()
+    }
+}
+
+impl<T: Serialize> InsertRowArgs<T> {
+    /// check the rows against the schema of the table, like the one of
+    /// [MochowClient::desc_table], all violations are returned at once
+    /// with the index of the offending row, like "rows[3].vector"
+    pub fn validate(&self, table: &DescriptTable) -> Result<(), ValidationError> {
+        validate_rows(table, &self.rows)
+    }
+}
+
+impl<T: Serialize> UpsertRowArgs<T> {
+    /// check the rows against the schema of the table, see [InsertRowArgs::validate]
+    pub fn validate(&self, table: &DescriptTable) -> Result<(), ValidationError> {
+        validate_rows(table, &self.rows)
+    }
+}
+
+fn validate_rows<T: Serialize>(table: &DescriptTable, rows: &[T]) -> Result<(), ValidationError> {
+    let mut violations = Violations::default();
+    for (i, row) in rows.iter().enumerate() {
+        let path = format!("rows[{}]", i);
+        match serde_json::to_value(row) {
+            Ok(Value::Object(row)) => check_row(&mut violations, &path, table, &row),
+            Ok(_) => violations.add(path, "row should be a json object"),
+            Err(e) => violations.add(path, format!("row can not be serialized: {}", e)),
+        }
+    }
+    violations.into_result()
+}
+
+fn check_row(
+    violations: &mut Violations,
+    path: &str,
+    table: &DescriptTable,
+    row: &serde_json::Map<String, Value>,
+) {
+    for field in &table.schema.fields {
+        let field_path = format!("{}.{}", path, field.field_name);
+        let value = match row.get(&field.field_name) {
+            Some(v) if !v.is_null() => v,
+            // the value of auto increment primary key is generated by the server
+            _ if field.auto_increment => continue,
+            _ if field.primary_key => {
+                violations.add(field_path, "primary key is required");
+                continue;
+            }
+            _ if field.not_null => {
+                violations.add(field_path, "not null field is required");
+                continue;
+            }
+            _ => continue,
+        };
+        if let FieldType::FLOAT_VECTOR = field.field_type {
+            match value.as_array() {
+                Some(vector) if vector.iter().all(Value::is_number) => {
+                    if let Some(dimension) = field.dimension {
+                        if vector.len() != dimension as usize {
+                            violations.add(
+                                field_path,
+                                format!(
+                                    "dimension should be {}, but got {}",
+                                    dimension,
+                                    vector.len()
+                                ),
+                            );
+                        }
+                    }
+                }
+                _ => violations.add(field_path, "should be an array of float"),
+            }
+        } else if !matches_type(&field.field_type, value) {
+            violations.add(
+                field_path,
+                format!("{} does not match type {:?}", value, field.field_type),
+            );
+        }
+    }
+    if !table.enable_dynamic_field {
+        for name in row.keys() {
+            if !table.schema.fields.iter().any(|f| &f.field_name == name) {
+                violations.add(
+                    format!("{}.{}", path, name),
+                    "unknown field, dynamic field is disabled",
+                );
+            }
+        }
+    }
+}
+
+// whether the json value is acceptable for the scalar field type
+fn matches_type(field_type: &FieldType, value: &Value) -> bool {
+    let signed = |min: i64, max: i64| value.as_i64().is_some_and(|v| v >= min && v <= max);
+    let unsigned = |max: u64| value.as_u64().is_some_and(|v| v <= max);
+    match field_type {
+        FieldType::BOOL => value.is_boolean(),
+        FieldType::INT8 => signed(i8::MIN.into(), i8::MAX.into()),
+        FieldType::Int16 => signed(i16::MIN.into(), i16::MAX.into()),
+        FieldType::INT32 => signed(i32::MIN.into(), i32::MAX.into()),
+        FieldType::INT64 => value.is_i64(),
+        FieldType::UINT8 => unsigned(u8::MAX.into()),
+        FieldType::Uint16 => unsigned(u16::MAX.into()),
+        FieldType::UINT32 => unsigned(u32::MAX.into()),
+        FieldType::UINT64 => value.is_u64(),
+        FieldType::FLOAT | FieldType::DOUBLE => value.is_number(),
+        FieldType::BINARY => value.is_string() || value.is_array(),
+        FieldType::DATE
+        | FieldType::DATETIME
+        | FieldType::TIMESTAMP
+        | FieldType::STRING
+        | FieldType::UUID
+        | FieldType::TEXT
+        | FieldType::TEXT_GBK
+        | FieldType::TEXT_GB18030 => value.is_string(),
+        FieldType::FLOAT_VECTOR => value.is_array(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::ValidationError;
+    use crate::{
+        error::SdkError,
+        mochow::{
+            api::*, client::MochowClient, config::ClientConfigurationBuilder,
+            testing::FakeMochowServer,
+        },
+    };
+
+    #[test]
+    fn test_validate_create_table_args() -> Result<()> {
+        let fields = vec![
+            FieldSchemaBuilder::default()
+                .field_name("id")
+                .field_type(FieldType::FLOAT)
+                .primary_key(true)
+                .partition_key(true)
+                .auto_increment(true)
+                .build()?,
+            FieldSchemaBuilder::default()
+                .field_name("2nd")
+                .field_type(FieldType::STRING)
+                .partition_key(true)
+                .build()?,
+            FieldSchemaBuilder::default()
+                .field_name("vector")
+                .field_type(FieldType::FLOAT_VECTOR)
+                .build()?,
+        ];
+        let indexes = vec![IndexSchemaBuilder::default()
+            .index_name("name_idx")
+            .field("name")
+            .index_type(IndexType::SECONDARY_INDEX)
+            .build()?];
+        let args = CreateTableArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .description("")
+            .replication(11_u32)
+            .schema(TableSchema { fields, indexes })
+            .build()?;
+        let err = args.validate().unwrap_err();
+        let paths: Vec<&str> = err.violations.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "replication",
+                "partition.partitionNum",
+                "schema.fields[0].primaryKey",
+                "schema.fields[0].partitionKey",
+                "schema.fields[0].autoIncrement",
+                "schema.fields[1].fieldName",
+                "schema.fields[2].dimension",
+                "schema.fields",
+                "schema.indexes[0].field",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_create_index_args() -> Result<()> {
+        let schema = TableSchema {
+            fields: vec![
+                FieldSchemaBuilder::default()
+                    .field_name("id")
+                    .field_type(FieldType::STRING)
+                    .primary_key(true)
+                    .build()?,
+                FieldSchemaBuilder::default()
+                    .field_name("vector")
+                    .field_type(FieldType::FLOAT_VECTOR)
+                    .dimension(10_u32)
+                    .build()?,
+            ],
+            indexes: vec![],
+        };
+        let indexes = vec![
+            IndexSchemaBuilder::default()
+                .index_name("hnsw_idx")
+                .field("vector")
+                .index_type(IndexType::HNSW)
+                .params(VectorIndexParams::HNSW(HNSWIndexParam {
+                    m: 2,
+                    ef_construction: 200,
+                }))
+                .build()?,
+            IndexSchemaBuilder::default()
+                .index_name("hnswpq_idx")
+                .field("vector")
+                .index_type(IndexType::HNSWPQ)
+                .metric_type(MetricType::L2)
+                .params(VectorIndexParams::HNSWPQ(HNSWPQIndexParam {
+                    m: 16,
+                    ef_construction: 2000,
+                    nsq: 3,
+                    sample_rate: 1.5,
+                }))
+                .build()?,
+            IndexSchemaBuilder::default()
+                .index_name("puck_idx")
+                .field("id")
+                .index_type(IndexType::PUCK)
+                .metric_type(MetricType::IP)
+                .params(VectorIndexParams::HNSW(HNSWIndexParam {
+                    m: 16,
+                    ef_construction: 200,
+                }))
+                .build()?,
+        ];
+        let args = CreateIndexArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .indexes(indexes)
+            .build()?;
+        let paths = |err: ValidationError| -> Vec<String> {
+            err.violations.into_iter().map(|v| v.path).collect()
+        };
+        assert_eq!(
+            paths(args.validate(None).unwrap_err()),
+            vec![
+                "indexes[0].metricType",
+                "indexes[0].params.M",
+                "indexes[1].params.efConstruction",
+                "indexes[1].params.sampleRate",
+                "indexes[2].params",
+            ]
+        );
+        assert_eq!(
+            paths(args.validate(Some(&schema)).unwrap_err()),
+            vec![
+                "indexes[0].metricType",
+                "indexes[0].params.M",
+                "indexes[1].params.efConstruction",
+                "indexes[1].params.sampleRate",
+                "indexes[1].params.NSQ",
+                "indexes[2].params",
+                "indexes[2].field",
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_rows() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let config = ClientConfigurationBuilder::default()
+            .account("root")
+            .api_key("fake_api_key")
+            .endpoint(server.endpoint())
+            .validate_rows(true)
+            .build()?;
+        let client = MochowClient::new_with_configuration(&config)?;
+        client.create_database("book").await?;
+        let args = CreateTableArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .description("")
+            .replication(1_u32)
+            .partition(Partition {
+                partition_type: PartitionType::HASH,
+                partition_num: 1,
+            })
+            .schema(TableSchema {
+                fields: vec![
+                    FieldSchemaBuilder::default()
+                        .field_name("id")
+                        .field_type(FieldType::STRING)
+                        .primary_key(true)
+                        .partition_key(true)
+                        .not_null(true)
+                        .build()?,
+                    FieldSchemaBuilder::default()
+                        .field_name("page")
+                        .field_type(FieldType::UINT32)
+                        .not_null(true)
+                        .build()?,
+                    FieldSchemaBuilder::default()
+                        .field_name("vector")
+                        .field_type(FieldType::FLOAT_VECTOR)
+                        .dimension(2_u32)
+                        .build()?,
+                ],
+                indexes: vec![],
+            })
+            .build()?;
+        client.create_table(&args).await?;
+
+        let rows = vec![
+            serde_json::json!({"id": "1", "page": 1, "vector": [0.1, 0.2]}),
+            serde_json::json!({"id": "2", "vector": [0.1]}),
+            serde_json::json!({"id": "3", "page": -1, "author": "unknown"}),
+            serde_json::json!({"page": 4}),
+        ];
+        let args = InsertRowArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .rows(rows)
+            .build()?;
+        match client.insert_row(&args).await {
+            Err(SdkError::ValidationError(e)) => {
+                let paths: Vec<&str> = e.violations.iter().map(|v| v.path.as_str()).collect();
+                assert_eq!(
+                    paths,
+                    vec![
+                        "rows[1].page",
+                        "rows[1].vector",
+                        "rows[2].page",
+                        "rows[2].author",
+                        "rows[3].id",
+                    ]
+                );
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        let args = UpsertRowArgsBuilder::default()
+            .database("book")
+            .table("segments")
+            .rows(vec![serde_json::json!({"id": "1", "page": 1})])
+            .build()?;
+        client.upsert_row(&args).await?;
+        Ok(())
+    }
+}
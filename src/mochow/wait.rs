@@ -0,0 +1,465 @@
+/*
+ * Copyright 2024 Baidu, Inc.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+/*
+wait for tables and indexes to reach the expected state by polling
+ */
+use std::time::{Duration, Instant};
+
+use futures_util::{stream, Stream};
+
+use crate::error::SdkError;
+
+use super::{api::*, client::MochowClient};
+
+const INITIAL_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_INTERVAL: Duration = Duration::from_secs(2);
+
+/// exponential backoff between polls, bounded by the deadline
+#[derive(Debug)]
+pub(crate) struct Backoff {
+    deadline: Instant,
+    interval: Duration,
+}
+
+impl Backoff {
+    pub(crate) fn new(timeout: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + timeout,
+            interval: INITIAL_INTERVAL,
+        }
+    }
+
+    /// sleep before the next poll, false if the deadline is reached
+    pub(crate) async fn wait(&mut self) -> bool {
+        let now = Instant::now();
+        if now >= self.deadline {
+            return false;
+        }
+        tokio::time::sleep(self.interval.min(self.deadline - now)).await;
+        self.interval = (self.interval * 2).min(MAX_INTERVAL);
+        true
+    }
+}
+
+pub(crate) fn is_not_exist(err: &SdkError) -> bool {
+    matches!(
+        err,
+        SdkError::ServiceError(e) if matches!(
+            e.server_code,
+            ServerErrorCode::DB_NOT_EXIST | ServerErrorCode::TABLE_NOT_EXIST
+        )
+    )
+}
+
+impl MochowClient {
+    /// create the table and wait until it's ready, see [MochowClient::wait_table_ready]
+    pub async fn create_table_and_wait(
+        &self,
+        args: &CreateTableArgs,
+        timeout: Duration,
+    ) -> Result<DescriptTable, SdkError> {
+        self.create_table(args).await?;
+        self.wait_table_ready(&args.database, &args.table, timeout)
+            .await
+    }
+
+    /// poll desc_table with backoff until the state of the table is NORMAL,
+    /// a table not visible yet is considered as creating, while a missing database fails at once,
+    /// fails with [SdkError::InvalidStateError] if the table is INVALID,
+    /// or [SdkError::TimeoutError] if it's not ready in time
+    pub async fn wait_table_ready(
+        &self,
+        data_base: &str,
+        table: &str,
+        timeout: Duration,
+    ) -> Result<DescriptTable, SdkError> {
+        let mut backoff = Backoff::new(timeout);
+        loop {
+            let state = match self.desc_table(data_base, table).await {
+                Ok(resp) => match resp.table.state {
+                    TableState::NORMAL => return Ok(resp.table),
+                    TableState::INVALID => {
+                        return Err(SdkError::InvalidStateError(format!(
+                            "table {}.{} is INVALID",
+                            data_base, table
+                        )))
+                    }
+                    state => format!("{:?}", state),
+                },
+                Err(SdkError::ServiceError(e))
+                    if e.server_code == ServerErrorCode::TABLE_NOT_EXIST =>
+                {
+                    "not exist".to_string()
+                }
+                Err(e) => return Err(e),
+            };
+            if !backoff.wait().await {
+                return Err(SdkError::TimeoutError(format!(
+                    "table {}.{} is not ready in {:?}, state: {}",
+                    data_base, table, timeout, state
+                )));
+            }
+        }
+    }
+
+    /// poll desc_table with backoff until the table disappears after DELETING,
+    /// fails with [SdkError::TimeoutError] if it still exists in time
+    pub async fn wait_table_dropped(
+        &self,
+        data_base: &str,
+        table: &str,
+        timeout: Duration,
+    ) -> Result<(), SdkError> {
+        let mut backoff = Backoff::new(timeout);
+        loop {
+            let state = match self.desc_table(data_base, table).await {
+                Ok(resp) => resp.table.state,
+                Err(e) if is_not_exist(&e) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            if !backoff.wait().await {
+                return Err(SdkError::TimeoutError(format!(
+                    "table {}.{} is not dropped in {:?}, state: {:?}",
+                    data_base, table, timeout, state
+                )));
+            }
+        }
+    }
+
+    /// rebuild the index and wait until the rebuilding is completed, that's when the index is NORMAL
+    /// with an index_major_version greater than the one before rebuilding
+    pub async fn rebuild_index_and_wait(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+        timeout: Duration,
+    ) -> Result<IndexSchema, SdkError> {
+        let index = self.desc_index(data_base, table, index_name).await?.index;
+        self.rebuild_index(data_base, table, index_name).await?;
+        self.wait_index_built(
+            data_base,
+            table,
+            index_name,
+            Some(index.index_major_version.unwrap_or_default()),
+            timeout,
+        )
+        .await
+    }
+
+    /// poll desc_index with backoff until the state of the index is NORMAL,
+    /// with after_major_version, until its index_major_version is also greater than it,
+    /// pass the version described before a rebuild to not take the old index as built,
+    /// fails with [SdkError::InvalidStateError] if the index is INVALID,
+    /// or [SdkError::TimeoutError] if it's not built in time,
+    /// see [MochowClient::rebuild_index_and_wait] for rebuilding
+    pub async fn wait_index_built(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+        after_major_version: Option<u64>,
+        timeout: Duration,
+    ) -> Result<IndexSchema, SdkError> {
+        let min_major_version = after_major_version.map_or(0, |version| version + 1);
+        let mut backoff = Backoff::new(timeout);
+        loop {
+            let index = self.desc_index(data_base, table, index_name).await?.index;
+            let major_version = index.index_major_version.unwrap_or_default();
+            match index.state {
+                Some(IndexState::NORMAL) if major_version >= min_major_version => return Ok(index),
+                Some(IndexState::INVALID) => {
+                    return Err(SdkError::InvalidStateError(format!(
+                        "index {} of table {}.{} is INVALID",
+                        index_name, data_base, table
+                    )))
+                }
+                _ => {}
+            }
+            if !backoff.wait().await {
+                return Err(SdkError::TimeoutError(format!(
+                    "index {} of table {}.{} is not built in {:?}, state: {:?}, major version: {}",
+                    index_name, data_base, table, timeout, index.state, major_version
+                )));
+            }
+        }
+    }
+
+    /// poll desc_index with backoff until the deleted index disappears,
+    /// an index of the same name can't be created before that,
+    /// fails with [SdkError::TimeoutError] if it's still described in time
+    pub async fn wait_index_deleted(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+        timeout: Duration,
+    ) -> Result<(), SdkError> {
+        let mut backoff = Backoff::new(timeout);
+        loop {
+            let state = match self.desc_index(data_base, table, index_name).await {
+                Ok(resp) => resp.index.state,
+                Err(SdkError::ServiceError(e))
+                    if e.server_code == ServerErrorCode::INDEX_NOT_EXIST =>
+                {
+                    return Ok(())
+                }
+                Err(e) => return Err(e),
+            };
+            if !backoff.wait().await {
+                return Err(SdkError::TimeoutError(format!(
+                    "index {} of table {}.{} is not deleted in {:?}, state: {:?}",
+                    index_name, data_base, table, timeout, state
+                )));
+            }
+        }
+    }
+
+    /// stream of the index polled by desc_index every interval, an item is yielded
+    /// when the state or index_major_version changes, starting with the current one,
+    /// the stream ends after yielding an error
+    /// ```rust,ignore
+    /// use futures_util::StreamExt;
+    ///
+    /// let mut changes = Box::pin(client.watch_index("book", "book_segments", "vector_idx", Duration::from_secs(1)));
+    /// while let Some(index) = changes.next().await {
+    ///     let index = index?;
+    ///     println!("{:?} {:?}", index.state, index.index_major_version);
+    /// }
+    /// ```
+    pub fn watch_index(
+        &self,
+        data_base: &str,
+        table: &str,
+        index_name: &str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<IndexSchema, SdkError>> {
+        let watcher = IndexWatcher {
+            client: self.clone(),
+            data_base: data_base.to_string(),
+            table: table.to_string(),
+            index_name: index_name.to_string(),
+            interval,
+            last: None,
+            polled: false,
+            done: false,
+        };
+        stream::unfold(watcher, |mut watcher| async move {
+            let item = watcher.next_change().await?;
+            Some((item, watcher))
+        })
+    }
+}
+
+struct IndexWatcher {
+    client: MochowClient,
+    data_base: String,
+    table: String,
+    index_name: String,
+    interval: Duration,
+    /// debug format of the state and the major version last yielded
+    last: Option<(String, Option<u64>)>,
+    polled: bool,
+    done: bool,
+}
+
+impl IndexWatcher {
+    // poll until the index changes, none if the stream is done
+    async fn next_change(&mut self) -> Option<Result<IndexSchema, SdkError>> {
+        if self.done {
+            return None;
+        }
+        loop {
+            if self.polled {
+                tokio::time::sleep(self.interval).await;
+            }
+            self.polled = true;
+            let index = match self
+                .client
+                .desc_index(&self.data_base, &self.table, &self.index_name)
+                .await
+            {
+                Ok(resp) => resp.index,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            let current = Some((format!("{:?}", index.state), index.index_major_version));
+            if current != self.last {
+                self.last = current;
+                return Some(Ok(index));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::Result;
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::mochow::{
+        config::ClientConfigurationBuilder,
+        middleware::{Fault, FaultInjectionMiddleware, FaultRuleBuilder, FaultSchedule},
+        testing::{create_table_args, field, FakeMochowServer},
+    };
+
+    #[tokio::test]
+    async fn test_wait_table() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        // the table is invisible to the first two polls
+        let faults = FaultInjectionMiddleware::new(0).with_rule(
+            FaultRuleBuilder::default()
+                .fault(Fault::ServerCode(404, ServerErrorCode::TABLE_NOT_EXIST))
+                .schedule(FaultSchedule::Sequence(vec![true, true]))
+                .path("/v1/table?desc")
+                .build()?,
+        );
+        let config = ClientConfigurationBuilder::default()
+            .account("root")
+            .api_key("fake_api_key")
+            .endpoint(server.endpoint())
+            .build()?;
+        let client = MochowClient::new_with_middleware(&config, vec![Arc::new(faults)])?;
+        client.create_database("book").await?;
+        let args = create_table_args("segments", vec![field("id", FieldType::STRING)], vec![]);
+        let table = client
+            .create_table_and_wait(&args, Duration::from_secs(5))
+            .await?;
+        assert!(matches!(table.state, TableState::NORMAL));
+        assert!(matches!(
+            client
+                .wait_table_ready("no_such_db", "segments", Duration::from_secs(5))
+                .await,
+            Err(SdkError::ServiceError(e)) if e.server_code == ServerErrorCode::DB_NOT_EXIST
+        ));
+
+        assert!(matches!(
+            client
+                .wait_table_dropped("book", "segments", Duration::from_millis(300))
+                .await,
+            Err(SdkError::TimeoutError(_))
+        ));
+        client.drop_table("book", "segments").await?;
+        client
+            .wait_table_dropped("book", "segments", Duration::from_secs(5))
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_and_watch_index() -> Result<()> {
+        let server = FakeMochowServer::start().await?;
+        let client = server.client()?;
+        client.create_database("book").await?;
+        let args = create_table_args(
+            "segments",
+            vec![
+                field("id", FieldType::STRING),
+                field("vector", FieldType::FLOAT_VECTOR),
+            ],
+            vec![IndexSchemaBuilder::default()
+                .index_name("vector_idx")
+                .field("vector")
+                .index_type(IndexType::HNSW)
+                .metric_type(MetricType::L2)
+                .params(VectorIndexParams::HNSW(HNSWIndexParam {
+                    m: 16,
+                    ef_construction: 200,
+                }))
+                .build()?],
+        );
+        // every build is reported BUILDING by two polls of desc_index
+        server.set_index_build_polls(2);
+        client.create_table(&args).await?;
+        let mut changes = Box::pin(client.watch_index(
+            "book",
+            "segments",
+            "vector_idx",
+            Duration::from_millis(20),
+        ));
+        let index = changes.next().await.unwrap()?;
+        assert!(matches!(index.state, Some(IndexState::BUILDING)));
+        assert_eq!(index.index_major_version, Some(0));
+        let index = changes.next().await.unwrap()?;
+        assert!(matches!(index.state, Some(IndexState::NORMAL)));
+        assert_eq!(index.index_major_version, Some(1));
+        let index = client
+            .wait_index_built(
+                "book",
+                "segments",
+                "vector_idx",
+                None,
+                Duration::from_secs(5),
+            )
+            .await?;
+        assert_eq!(index.index_major_version, Some(1));
+
+        client
+            .rebuild_index("book", "segments", "vector_idx")
+            .await?;
+        let index = changes.next().await.unwrap()?;
+        assert!(matches!(index.state, Some(IndexState::BUILDING)));
+        assert_eq!(index.index_major_version, Some(1));
+        let index = client
+            .wait_index_built(
+                "book",
+                "segments",
+                "vector_idx",
+                Some(1),
+                Duration::from_secs(5),
+            )
+            .await?;
+        assert!(matches!(index.state, Some(IndexState::NORMAL)));
+        assert_eq!(index.index_major_version, Some(2));
+        let index = changes.next().await.unwrap()?;
+        assert!(matches!(index.state, Some(IndexState::NORMAL)));
+        assert_eq!(index.index_major_version, Some(2));
+
+        let index = client
+            .rebuild_index_and_wait("book", "segments", "vector_idx", Duration::from_secs(5))
+            .await?;
+        assert_eq!(index.index_major_version, Some(3));
+
+        // a build never completing in time
+        server.set_index_build_polls(1000);
+        client
+            .rebuild_index("book", "segments", "vector_idx")
+            .await?;
+        assert!(matches!(
+            client
+                .wait_index_built(
+                    "book",
+                    "segments",
+                    "vector_idx",
+                    Some(3),
+                    Duration::from_millis(300),
+                )
+                .await,
+            Err(SdkError::TimeoutError(_))
+        ));
+
+        client
+            .delete_index("book", "segments", "vector_idx")
+            .await?;
+        assert!(changes.next().await.unwrap().is_err());
+        assert!(changes.next().await.is_none());
+        Ok(())
+    }
+}